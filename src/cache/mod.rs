@@ -15,7 +15,9 @@
 //! kept in the ModuleCache and instead should be in a special data structure for
 //! the relevant phase. An example is the `llvm::Generator` in the llvm codegen phase.
 use crate::cache::unsafecache::UnsafeCache;
+use crate::error;
 use crate::error::location::{Locatable, Location};
+use crate::error::MessageCounts;
 use crate::nameresolution::NameResolver;
 use crate::parser::ast::{Ast, Definition, EffectDefinition, TraitDefinition, TraitImpl, Extern};
 use crate::types::traits::{ConstraintSignature, RequiredImpl, RequiredTrait, TraitConstraintId};
@@ -25,6 +27,7 @@ use crate::util::stdlib_dir;
 
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 mod counter;
 mod unsafecache;
@@ -110,6 +113,11 @@ pub struct ModuleCache<'a> {
     /// to all functions in that set. Once the key'd function finishes compiling we can
     /// generalize all the functions in the set and add trait constraints at once.
     pub mutual_recursion_sets: Vec<MutualRecursionSet>,
+
+    /// The number of messages of each severity issued since this cache was created. Messages
+    /// on this thread are counted towards the most recently created cache only, so a cache kept
+    /// alive while a later compilation runs does not count that compilation's messages.
+    message_counts: Arc<Mutex<MessageCounts>>,
 }
 
 #[derive(Debug)]
@@ -228,6 +236,24 @@ pub struct DefinitionInfo<'a> {
     /// A count of how many times was this variable referenced in the program.
     /// Used primarily for issuing unused warnings.
     pub uses: u32,
+
+    /// Whether the last value assigned to this variable with `:=` has been read yet.
+    /// Used for issuing warnings for assignments which are never read.
+    pub assignment_state: AssignmentState<'a>,
+}
+
+/// Tracks the last assignment to a local `mut` variable. Variables which may be
+/// aliased, e.g. by being passed to a function or captured by a closure, are
+/// Untracked since their value can be read without referring to them by name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AssignmentState<'a> {
+    Untracked,
+
+    /// Every value assigned to this variable so far has been read
+    Read,
+
+    /// The variable was last assigned at this location and has not been read since
+    Unread(Location<'a>),
 }
 
 impl<'a> Locatable<'a> for DefinitionInfo<'a> {
@@ -358,6 +384,9 @@ impl<'a> Locatable<'a> for EffectInfo<'a> {
 
 impl<'a> ModuleCache<'a> {
     pub fn new(project_directory: &Path) -> ModuleCache<'a> {
+        let message_counts = Arc::default();
+        error::count_messages_in(&message_counts);
+
         ModuleCache {
             relative_roots: vec![project_directory.to_owned(), stdlib_dir()],
             // Really wish you could do ..Default::default() for the remaining fields
@@ -376,9 +405,30 @@ impl<'a> ModuleCache<'a> {
             call_stack: vec![],
             mutual_recursion_sets: vec![],
             effect_infos: vec![],
+            message_counts,
         }
     }
 
+    /// The number of errors issued while compiling with this cache
+    pub fn error_count(&self) -> usize {
+        self.message_counts().errors
+    }
+
+    /// The number of warnings issued while compiling with this cache
+    pub fn warning_count(&self) -> usize {
+        self.message_counts().warnings
+    }
+
+    /// The number of notes issued while compiling with this cache
+    pub fn note_count(&self) -> usize {
+        self.message_counts().notes
+    }
+
+    /// The number of messages of each severity issued while compiling with this cache
+    pub fn message_counts(&self) -> MessageCounts {
+        *self.message_counts.lock().unwrap()
+    }
+
     pub fn push_filepath(&mut self, path: PathBuf) -> &'a Path {
         let index = self.filepaths.len();
         self.filepaths.push(path);
@@ -401,6 +451,7 @@ impl<'a> ModuleCache<'a> {
             undergoing_type_inference: false,
             mutually_recursive_variables: vec![],
             ignore_unused_warning: name.starts_with('_'),
+            assignment_state: AssignmentState::Untracked,
         });
         DefinitionInfoId(id)
     }
@@ -654,3 +705,38 @@ impl<'c> std::ops::IndexMut<ImplScopeId> for ModuleCache<'c> {
         &mut self.impl_scopes[index.0]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser;
+    use crate::types::typechecker;
+
+    /// Compile `source` up to and including type inference, as `ante --check` does
+    fn check<'a>(filename: &'a Path, source: &'a str, cache: &mut ModuleCache<'a>) {
+        error::cache_source(filename, source);
+        let tokens = Lexer::new(filename, source).collect::<Vec<_>>();
+        let root = parser::parse(&tokens).unwrap();
+
+        if NameResolver::start(root, cache).is_ok() {
+            let ast = cache.parse_trees.get_mut(0).unwrap();
+            typechecker::infer_ast(ast, cache);
+        }
+    }
+
+    #[test]
+    fn caches_count_only_their_own_messages() {
+        let mut first = ModuleCache::new(Path::new("."));
+        check(Path::new("first_cache.an"), "a: I32 = \"one\"\nb: I32 = \"two\"\n", &mut first);
+        assert_eq!(first.error_count(), 2);
+
+        // The first cache is still alive while the second compiles
+        let mut second = ModuleCache::new(Path::new("."));
+        check(Path::new("second_cache.an"), "c = 3\n", &mut second);
+        error::ErrorMessage::warning("warning", Location::builtin()).emit(&mut vec![]).unwrap();
+
+        assert_eq!(second.message_counts(), MessageCounts { errors: 0, warnings: 1, notes: 0 });
+        assert_eq!(first.message_counts(), MessageCounts { errors: 2, warnings: 0, notes: 0 });
+    }
+}
@@ -1,16 +1,25 @@
 use clap::{Parser, ValueEnum, ValueHint};
 use clap_complete::Shell;
 
+use crate::error::{CaretStyle, ErrorType, MessageFormat};
+
 #[derive(Parser, Debug)]
 pub struct Completions {
     #[arg(long)]
     pub shell_completion: Shell,
 }
 
+#[derive(Parser, Debug)]
+pub struct Explain {
+    /// Print a detailed explanation of the given error code, e.g. E0001
+    #[arg(long)]
+    pub explain: String,
+}
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
-    /// Path to the source file
+    /// Path to the source file, or '-' to read it from standard input
     #[arg(value_hint=ValueHint::FilePath)]
     pub file: String,
 
@@ -49,10 +58,76 @@ pub struct Cli {
     #[arg(short = 'O', default_value = "0", value_parser = validate_opt_argument)]
     pub opt_level: char,
 
-    /// Use plaintext and an indicator line instead of color for pointing out error locations
+    /// Use plaintext and an indicator line instead of color for pointing out error locations.
+    /// This is already the default when the NO_COLOR environment variable is set or stderr is not a terminal,
+    /// unless ANTE_COLOR is set to `always`. Setting ANTE_COLOR to `never` also disables color.
     #[arg(long)]
     pub no_color: bool,
 
+    /// Draw an indicator line beneath error locations even when output is colored
+    #[arg(long)]
+    pub always_underline: bool,
+
+    /// Sets the characters the indicator line beneath error locations is drawn with:
+    /// `caret` draws `^^^^` while `tilde` draws `^~~~` as in gcc and clang
+    #[arg(long, value_enum, default_value = "caret")]
+    pub caret_style: CaretStyle,
+
+    /// Sets the character used in place of `^` in the indicator line beneath error locations
+    #[arg(long, default_value_t = '^')]
+    pub caret_char: char,
+
+    /// Sets the character used in place of `~` in the indicator line when `--caret-style tilde` is used
+    #[arg(long, default_value_t = '~')]
+    pub underline_char: char,
+
+    /// Make error locations links which open their file when clicked, in terminals which support them.
+    /// Links are only shown when output is colored
+    #[arg(long)]
+    pub hyperlinks: bool,
+
+    /// Sets the colors used for errors, warnings, and notes. This is either the name of a builtin
+    /// theme ('default' or 'high-contrast') or a theme file. Each line of the file is blank, a
+    /// `#` comment, or names part of a message (error, warning, note, label, help, or path) and
+    /// gives its style as a double-quoted string, e.g. `error = "bold bright red"`. Unlike TOML,
+    /// comments must be on their own line and single quotes and escapes are not supported
+    #[arg(long)]
+    pub theme: Option<String>,
+
+    /// Highlight only the parts of two mismatched types which differ in type errors, rather than
+    /// highlighting each type in its entirety. Highlighting is only shown when output is colored
+    #[arg(long)]
+    pub diff_types: bool,
+
+    /// Abbreviate types nested more than this many types deep as `...` when showing them in messages
+    #[arg(long)]
+    pub max_type_depth: Option<usize>,
+
+    /// Shorten source lines in messages which are longer than this many columns, showing only
+    /// the part of the line around the location pointed to
+    #[arg(long)]
+    pub max_line_width: Option<usize>,
+
+    /// Sets the number of columns tabs are expanded to when showing source lines in messages
+    #[arg(long, default_value_t = 4)]
+    pub tab_width: usize,
+
+    /// Sets the number of lines of source shown above and below each location pointed to in messages
+    #[arg(long, default_value_t = 1)]
+    pub context_lines: usize,
+
+    /// Issue all warnings as errors, causing compilation to fail if any are found
+    #[arg(long)]
+    pub warnings_as_errors: bool,
+
+    /// Only print messages at least this severe, e.g. `warning` to hide notes or `error` to print only errors
+    #[arg(long, value_enum, default_value = "note")]
+    pub min_severity: ErrorType,
+
+    /// Sets the format errors, warnings, and notes are printed in
+    #[arg(long, value_enum, default_value = "human")]
+    pub message_format: MessageFormat,
+
     /// Delete the resulting binary after compiling
     #[arg(long, short, group = "compile_mode")]
     pub delete_binary: bool,
@@ -0,0 +1,351 @@
+//! codes.rs - Defines the stable error codes attached to each kind of
+//! error the compiler can issue. Codes are shown in the header of an
+//! error message (e.g. `error[E0001]: ...`) so that users and tooling can
+//! refer to a kind of error without depending on its exact wording.
+//!
+//! Once assigned, a code should never be reused for a different kind of error.
+
+/// Defines the ErrorCode enum from a list of each variant and its code, along with
+/// `ErrorCode::ALL` and `ErrorCode::code`. Listing each variant once here means a
+/// new variant cannot be left out of `ALL` or be missing a code.
+macro_rules! error_codes {
+    ( $( $(#[$attr:meta])* $variant:ident = $code:literal, )* ) => {
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum ErrorCode {
+            $( $(#[$attr])* $variant, )*
+        }
+
+        impl ErrorCode {
+            /// Every error code, in the order they were assigned
+            pub const ALL: &'static [ErrorCode] = &[ $( ErrorCode::$variant, )* ];
+
+            /// Returns the stable code for this kind of error, e.g. "E0001"
+            pub fn code(self) -> &'static str {
+                match self {
+                    $( ErrorCode::$variant => $code, )*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// Two types were expected to be equal but were not
+    TypeMismatch = "E0001",
+
+    /// A function was called with a different number of arguments than it declares
+    WrongParameterCount = "E0002",
+
+    /// No impl was found to satisfy a trait constraint
+    NoMatchingImpl = "E0003",
+
+    /// More than one impl could satisfy a trait constraint
+    MultipleMatchingImpls = "E0004",
+
+    /// A match or handle expression does not cover every possible case
+    NonExhaustive = "E0005",
+
+    /// A pattern was written using syntax that is not valid in patterns
+    InvalidPattern = "E0006",
+
+    /// A name was defined more than once in the same scope
+    AlreadyDefined = "E0007",
+
+    /// A name, type, trait, or module was not found in scope
+    NotFound = "E0008",
+
+    /// A type or trait was given a different number of type arguments than it requires
+    WrongTypeArgumentCount = "E0009",
+
+    /// The definitions in an impl do not match those required by its trait
+    ImplDefinitionMismatch = "E0010",
+
+    /// An effect was declared or used incorrectly
+    InvalidEffect = "E0011",
+
+    /// A primitive integer or float type was given an argument that isn't an integer or float kind
+    InvalidPrimitiveArgument = "E0012",
+
+    /// An imported module could not be opened
+    ImportFailed = "E0013",
+
+    /// The source could not be lexed or parsed
+    Syntax = "E0014",
+
+    /// A type was inferred to contain itself
+    InfiniteType = "E0015",
+
+    /// An integer literal is too large for its integer type
+    IntegerOverflow = "E0016",
+
+    /// A type contains itself without any indirection
+    InfiniteSize = "E0017",
+
+    /// A variable which was not declared mutable was assigned to
+    AssignToImmutable = "E0018",
+}
+
+impl ErrorCode {
+    /// Returns the long-form description of this error shown by `ante --explain`
+    pub fn explanation(self) -> &'static str {
+        match self {
+            ErrorCode::TypeMismatch => {
+                "A value was used where a value of a different type was expected.
+
+Erroneous code example:
+
+    foo (x: I32) = x + 1
+    foo \"hello\"
+
+`foo` expects an I32 but was given a String. Either change the argument
+or change the type the function expects."
+            },
+            ErrorCode::WrongParameterCount => {
+                "A function was called with a different number of arguments than it
+was declared with.
+
+Erroneous code example:
+
+    add (x: I32) (y: I32) = x + y
+    add 1 2 3
+
+`add` takes 2 arguments but was given 3."
+            },
+            ErrorCode::NoMatchingImpl => {
+                "A trait was required for a type, but no impl of that trait exists for it.
+
+Erroneous code example:
+
+    trait Foo a with
+        foo: a -> Unit
+
+    foo 3
+
+There is no `impl Foo I32`, so `foo` cannot be called on 3. Add an impl
+for the type, or pass a value of a type which already has one."
+            },
+            ErrorCode::MultipleMatchingImpls => {
+                "More than one impl could satisfy a trait constraint, so the compiler
+could not decide which one to use.
+
+Erroneous code example:
+
+    trait Foo a with
+        foo: a -> Unit
+
+    impl Foo a with
+        foo _ = ()
+
+    impl Foo I32 with
+        foo _ = ()
+
+    foo 3
+
+Both impls match `Foo I32`. Remove one of them or make them disjoint."
+            },
+            ErrorCode::NonExhaustive => {
+                "A match or handle expression does not cover every possible value
+of the expression being matched on.
+
+Erroneous code example:
+
+    match Some 3
+    | None -> 0
+
+The `Some _` case is missing. Add a branch for it, or a catch-all `_` branch."
+            },
+            ErrorCode::InvalidPattern => {
+                "A pattern was written using syntax that is not valid in that position.
+
+Erroneous code example:
+
+    f (Some x) = x
+
+Irrefutable patterns, such as those in definitions and function
+parameters, must match every possible value. They may contain names,
+tuples, and type annotations, but not literals or constructors of types
+with more than one variant. Use a `match` expression instead."
+            },
+            ErrorCode::AlreadyDefined => {
+                "A name was defined more than once in the same scope.
+
+Erroneous code example:
+
+    a = 1
+    a = 2
+
+Rename one of the definitions. Definitions in a nested scope (such as a
+function body) may still shadow those in an outer scope."
+            },
+            ErrorCode::NotFound => {
+                "A variable, type, trait, or module was referred to but is not in scope.
+
+Erroneous code example:
+
+    x = y + 1
+
+`y` is never defined. Check the spelling of the name, or import the
+module which defines it."
+            },
+            ErrorCode::WrongTypeArgumentCount => {
+                "A type or trait was given a different number of type arguments than
+it was declared with.
+
+Erroneous code example:
+
+    type Pair a b = first: a, second: b
+
+    foo (p: Pair I32) = p
+
+`Pair` takes 2 type arguments but was given 1."
+            },
+            ErrorCode::ImplDefinitionMismatch => {
+                "The definitions in an impl do not match those declared by its trait.
+
+Erroneous code example:
+
+    trait Foo a with
+        foo: a -> Unit
+
+    impl Foo I32 with
+        bar _ = ()
+
+`bar` is not part of `Foo`, and `foo` is missing. Every definition
+declared in a trait must be given in each of its impls, and nothing else."
+            },
+            ErrorCode::InvalidEffect => {
+                "An effect was declared or handled incorrectly.
+
+Erroneous code example:
+
+    effect Log with
+        log: String
+
+Each declaration in an effect must have a function type, such as
+`log: String -> Unit`. Similarly, each pattern in a `handle` expression
+must be a call to one of the functions of an effect."
+            },
+            ErrorCode::InvalidPrimitiveArgument => {
+                "A primitive integer or float type was given a type argument which
+is not an integer or float kind.
+
+Erroneous code example:
+
+    x: Int String = 3
+
+The argument to `Int` must be an integer kind such as `I32` or `U8`, and
+the argument to `Float` must be a float kind such as `F64`."
+            },
+            ErrorCode::ImportFailed => {
+                "An imported module could not be opened.
+
+Erroneous code example:
+
+    import DoesNotExist
+
+Make sure the module's file exists relative to the importing file, and
+that its name is spelled the same as the file name."
+            },
+            ErrorCode::Syntax => {
+                "The source could not be lexed or parsed.
+
+Erroneous code example:
+
+    foo x = (x + 1
+
+The parenthesis opened before `x` is never closed. The error message
+points to where the parser stopped, which may be after the actual mistake."
+            },
+            ErrorCode::InfiniteType => {
+                "A type was inferred to contain itself, which would make it infinitely large.
+
+Erroneous code example:
+
+    apply_self f = f f
+
+`f` is called with itself as an argument, so its type `a` would need to
+equal `a -> b`. Add a type annotation or restructure the code so that no
+value is used as an argument to itself."
+            },
+            ErrorCode::IntegerOverflow => {
+                "An integer literal is too large to fit in its integer type.
+
+Erroneous code example:
+
+    x = 300u8
+
+The largest value a U8 can hold is 255. Use a larger integer type, such as
+`300u16`, or a smaller literal."
+            },
+            ErrorCode::InfiniteSize => {
+                "A type contains itself without any indirection, so it would be infinitely large.
+
+Erroneous code example:
+
+    type List a =
+       | Nil
+       | Cons a (List a)
+
+Storing a `List a` directly inside of each `Cons` would require every list
+to contain another list. Store a reference to it instead with
+`Cons a (Ref (List a))`."
+            },
+            ErrorCode::AssignToImmutable => {
+                "A variable was assigned to with `:=` but was not declared mutable.
+
+Erroneous code example:
+
+    x = 1
+    x := 2
+
+Only mutable variables may be assigned to after they are defined. Declare
+`x` mutable with `x = mut 1` to allow this."
+            },
+        }
+    }
+}
+
+/// Returns the long-form description for the given error code (e.g. "E0001"),
+/// or None if no error has that code.
+pub fn explain(code: &str) -> Option<&'static str> {
+    ErrorCode::ALL.iter().find(|error| error.code() == code).map(|error| error.explanation())
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn codes_are_unique_and_well_formed() {
+        let mut seen = HashSet::new();
+        for error in ErrorCode::ALL {
+            let code = error.code();
+            assert!(seen.insert(code), "{} is used by more than one ErrorCode", code);
+
+            let digits = code.strip_prefix('E').unwrap_or_default();
+            assert!(digits.len() == 4 && digits.bytes().all(|c| c.is_ascii_digit()), "malformed code {}", code);
+        }
+    }
+
+    #[test]
+    fn every_code_can_be_explained() {
+        for error in ErrorCode::ALL {
+            assert_eq!(explain(error.code()), Some(error.explanation()));
+            assert!(!error.explanation().trim().is_empty(), "{} has no explanation", error);
+        }
+    }
+
+    #[test]
+    fn unknown_codes_have_no_explanation() {
+        assert_eq!(explain("E9999"), None);
+        assert_eq!(explain("e0001"), None);
+        assert_eq!(explain(""), None);
+    }
+}
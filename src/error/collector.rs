@@ -0,0 +1,292 @@
+//! collector.rs - Defines the DiagnosticsCollector, a buffer of ErrorMessages
+//! which have been issued but not yet printed. Collecting messages first lets
+//! a pass inspect, reorder, or batch them before they reach stderr.
+//!
+//! The error, warning, and note macros can push into a collector instead of
+//! printing directly by prefixing their arguments with it, e.g.
+//! `warning!(collector => location, "...")`.
+use super::{file_group_header, ErrorMessage, ErrorType, MessageFormat, MESSAGE_FORMAT};
+
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::{Component, Path};
+
+#[derive(Debug, Default)]
+pub struct DiagnosticsCollector<'a> {
+    messages: Vec<ErrorMessage<'a>>,
+
+    /// If set, errors pushed after this many have been collected are dropped
+    max_errors: Option<usize>,
+    error_count: usize,
+
+    /// True once an error has been dropped because of max_errors
+    aborted: bool,
+}
+
+impl<'a> DiagnosticsCollector<'a> {
+    pub fn new() -> DiagnosticsCollector<'a> {
+        DiagnosticsCollector { messages: vec![], max_errors: None, error_count: 0, aborted: false }
+    }
+
+    /// Create a collector which stops collecting errors once `max_errors` have been pushed.
+    /// Warnings and notes are still collected after this point.
+    pub fn with_max_errors(max_errors: usize) -> DiagnosticsCollector<'a> {
+        DiagnosticsCollector { messages: vec![], max_errors: Some(max_errors), error_count: 0, aborted: false }
+    }
+
+    pub fn push(&mut self, message: ErrorMessage<'a>) {
+        if message.is_error() {
+            if self.should_abort() {
+                if !self.aborted {
+                    self.messages.push(ErrorMessage::note("too many errors; aborting", message.location));
+                    self.aborted = true;
+                }
+                return;
+            }
+            self.error_count += 1;
+        }
+        self.messages.push(message);
+    }
+
+    /// True once the maximum number of errors has been collected, in which
+    /// case the pass issuing them should stop early.
+    pub fn should_abort(&self) -> bool {
+        self.max_errors.is_some_and(|max_errors| self.error_count >= max_errors)
+    }
+
+    /// All collected messages, in the order they were pushed
+    pub fn messages(&self) -> &[ErrorMessage<'a>] {
+        &self.messages
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ErrorMessage<'a>> {
+        self.messages.iter().filter(|message| message.is_error())
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ErrorMessage<'a>> {
+        self.messages.iter().filter(|message| message.severity() == ErrorType::Warning)
+    }
+
+    /// All collected messages located in the given file, in the order they were pushed.
+    /// Paths are compared by their components ignoring any `.` so that `./a.an` and `a.an`
+    /// refer to the same file, but they are otherwise compared exactly as given.
+    pub fn for_file<'p>(&'p self, path: &'p Path) -> impl Iterator<Item = &'p ErrorMessage<'a>> {
+        self.messages.iter().filter(move |message| same_file(message.location.filename, path))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Sort the collected messages so that they are issued in a deterministic order
+    pub fn sort(&mut self) {
+        self.messages.sort();
+    }
+
+    /// Sort the collected messages by file, then line, then column. Unlike `sort`, this
+    /// compares only positions so messages issued at the same position remain in the
+    /// order they were pushed.
+    pub fn sort_by_location(&mut self) {
+        self.messages.sort_by_key(|message| {
+            let start = message.location.start;
+            (message.location.filename, start.line, start.column)
+        });
+    }
+
+    /// Remove any message identical to one pushed before it, keeping the first occurrence.
+    /// Messages are identical if they have the same kind, file, position, and message text.
+    pub fn dedup(&mut self) {
+        let mut seen = BTreeSet::new();
+        self.messages.retain(|message| {
+            let location = message.location;
+            let key = (location.filename, location.start, location.end, message.error_type);
+            seen.insert((key, (*message.msg).to_owned()))
+        });
+    }
+
+    /// Write out each collected message in order, printing the name of each file once
+    /// before the consecutive messages within it rather than at the start of each one.
+    /// This is best used after `sort_by_location` so each file forms a single group.
+    /// Only human-readable messages are grouped, in other formats this is the same as `emit_all`.
+    pub fn emit_grouped_by_file(&self, out: &mut impl Write) -> std::io::Result<()> {
+        if *MESSAGE_FORMAT.read().unwrap() != MessageFormat::Human {
+            return self.emit_all(out);
+        }
+
+        let mut current_file = None;
+        for message in self.messages.iter().filter(|message| message.is_shown()) {
+            if current_file != Some(message.location.filename) {
+                writeln!(out, "{}", file_group_header(message.location.filename))?;
+                current_file = Some(message.location.filename);
+            }
+            message.count_issued();
+            writeln!(out, "{}", message.in_file_group())?;
+        }
+        Ok(())
+    }
+
+    /// Write out each collected message in order. As with emitting a single
+    /// ErrorMessage, this is what counts any errors towards the error count.
+    pub fn emit_all(&self, out: &mut impl Write) -> std::io::Result<()> {
+        for message in &self.messages {
+            message.emit(out)?;
+        }
+        Ok(())
+    }
+}
+
+/// True if both paths have the same components after removing any `.` components
+fn same_file(a: &Path, b: &Path) -> bool {
+    fn components(path: &Path) -> impl Iterator<Item = Component<'_>> {
+        path.components().filter(|component| *component != Component::CurDir)
+    }
+    components(a).eq(components(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::get_message_counts;
+    use crate::error::location::{EndPosition, Location, Position};
+
+    fn location(filename: &'static str, start: usize, end: usize) -> Location<'static> {
+        let start = Position { index: start, line: 1, column: start as u16 + 1 };
+        Location::new(Path::new(filename), start, EndPosition::new(end))
+    }
+
+    /// The text of each collected message, in order
+    fn texts<'c>(collector: &'c DiagnosticsCollector) -> Vec<&'c str> {
+        collector.messages().iter().map(|message| &*message.msg).collect()
+    }
+
+    #[test]
+    fn messages_are_counted_once_emitted() {
+        let mut collector = DiagnosticsCollector::new();
+        assert!(collector.is_empty());
+
+        collector.push(ErrorMessage::warning("warning", location("a.an", 0, 1)));
+        collector.push(ErrorMessage::error("error", location("a.an", 1, 2)));
+        collector.push(ErrorMessage::note("note", location("a.an", 2, 3)));
+
+        assert_eq!(texts(&collector), ["warning", "error", "note"]);
+        assert_eq!(collector.errors().map(|error| &*error.msg).collect::<Vec<_>>(), ["error"]);
+        assert_eq!(collector.warnings().map(|warning| &*warning.msg).collect::<Vec<_>>(), ["warning"]);
+
+        let before = get_message_counts();
+        let mut out = vec![];
+        collector.emit_all(&mut out).unwrap();
+
+        let issued = get_message_counts() - before;
+        assert_eq!((issued.errors, issued.warnings, issued.notes), (1, 1, 1));
+        assert!(String::from_utf8(out).unwrap().contains("warning"));
+    }
+
+    #[test]
+    fn dedup_removes_identical_messages() {
+        let mut collector = DiagnosticsCollector::new();
+        collector.push(ErrorMessage::error("first", location("a.an", 0, 3)));
+        collector.push(ErrorMessage::error("first", location("a.an", 0, 3)));
+        collector.push(ErrorMessage::warning("first", location("a.an", 0, 3)));
+        collector.push(ErrorMessage::error("second", location("a.an", 0, 3)));
+        collector.dedup();
+
+        let messages: Vec<_> = collector.messages().iter().map(|message| (message.severity(), &*message.msg)).collect();
+        assert_eq!(
+            messages,
+            [(ErrorType::Error, "first"), (ErrorType::Warning, "first"), (ErrorType::Error, "second")]
+        );
+    }
+
+    #[test]
+    fn dedup_keeps_messages_at_the_same_offsets_in_different_files() {
+        let mut collector = DiagnosticsCollector::new();
+        collector.push(ErrorMessage::error("Unused variable `x`", location("a.an", 4, 5)));
+        collector.push(ErrorMessage::error("Unused variable `x`", location("b.an", 4, 5)));
+        collector.dedup();
+
+        let files: Vec<_> = collector.messages().iter().map(|message| message.location.filename).collect();
+        assert_eq!(files, [Path::new("a.an"), Path::new("b.an")]);
+    }
+
+    #[test]
+    fn sort_by_location_orders_by_file_then_position() {
+        let mut collector = DiagnosticsCollector::new();
+        collector.push(ErrorMessage::error("b.an first", location("b.an", 0, 1)));
+        collector.push(ErrorMessage::error("a.an second", location("a.an", 4, 5)));
+        collector.push(ErrorMessage::error("a.an first", location("a.an", 0, 1)));
+        collector.push(ErrorMessage::warning("a.an second, pushed last", location("a.an", 4, 5)));
+        collector.sort_by_location();
+
+        assert_eq!(texts(&collector), ["a.an first", "a.an second", "a.an second, pushed last", "b.an first"]);
+    }
+
+    #[test]
+    fn errors_past_the_limit_are_replaced_by_a_single_note() {
+        let mut collector = DiagnosticsCollector::with_max_errors(2);
+        collector.push(ErrorMessage::error("first", location("a.an", 0, 1)));
+        assert!(!collector.should_abort());
+
+        collector.push(ErrorMessage::error("second", location("a.an", 1, 2)));
+        assert!(collector.should_abort());
+
+        collector.push(ErrorMessage::error("third", location("a.an", 2, 3)));
+        collector.push(ErrorMessage::warning("warning", location("a.an", 3, 4)));
+        collector.push(ErrorMessage::error("fourth", location("a.an", 4, 5)));
+
+        assert_eq!(texts(&collector), ["first", "second", "too many errors; aborting", "warning"]);
+        assert_eq!(collector.errors().count(), 2);
+    }
+
+    #[test]
+    fn collectors_without_a_limit_never_abort() {
+        let mut collector = DiagnosticsCollector::new();
+        for i in 0..100 {
+            collector.push(ErrorMessage::error("error", location("a.an", i, i + 1)));
+        }
+        assert!(!collector.should_abort());
+        assert_eq!(collector.errors().count(), 100);
+    }
+
+    #[test]
+    fn emit_grouped_by_file_names_each_file_once() {
+        crate::error::cache_source(Path::new("grouped_a.an"), "x = 1\ny = 2\n");
+        crate::error::cache_source(Path::new("grouped_b.an"), "z = 3\n");
+
+        let mut collector = DiagnosticsCollector::new();
+        let second_line = Position { index: 6, line: 2, column: 1 };
+        let second_line = Location::new(Path::new("grouped_a.an"), second_line, EndPosition::new(7));
+        collector.push(ErrorMessage::error("second", second_line));
+        collector.push(ErrorMessage::warning("third", location("grouped_b.an", 0, 1)));
+        collector.push(ErrorMessage::error("first", location("grouped_a.an", 0, 1)));
+        collector.sort_by_location();
+
+        let before = get_message_counts();
+        let mut out = vec![];
+        collector.emit_grouped_by_file(&mut out).unwrap();
+
+        let issued = get_message_counts() - before;
+        assert_eq!((issued.errors, issued.warnings), (2, 1));
+
+        let out = String::from_utf8(out).unwrap();
+        let headers: Vec<_> = out.lines().filter(|line| line.ends_with(".an:")).collect();
+        assert_eq!(headers, ["grouped_a.an:", "grouped_b.an:"]);
+
+        let messages: Vec<_> = out.lines().filter(|line| line.contains('\t')).collect();
+        assert_eq!(messages, ["1:1\terror: first", "2:1\terror: second", "1:1\twarning: third"]);
+    }
+
+    #[test]
+    fn for_file_ignores_current_directory_components() {
+        let mut collector = DiagnosticsCollector::new();
+        collector.push(ErrorMessage::error("in a.an", location("./a.an", 0, 1)));
+        collector.push(ErrorMessage::error("in b.an", location("b.an", 0, 1)));
+        collector.push(ErrorMessage::error("in dir/a.an", location("dir/a.an", 0, 1)));
+        collector.push(ErrorMessage::error("in a.an again", location("a.an", 1, 2)));
+
+        let in_file = |path| collector.for_file(Path::new(path)).map(|message| &*message.msg).collect::<Vec<_>>();
+        assert_eq!(in_file("a.an"), ["in a.an", "in a.an again"]);
+        assert_eq!(in_file("./a.an"), ["in a.an", "in a.an again"]);
+        assert_eq!(in_file("./dir/./a.an"), ["in dir/a.an"]);
+        assert!(in_file("c.an").is_empty());
+    }
+}
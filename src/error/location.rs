@@ -2,8 +2,13 @@
 //! Locations throughout the compiler. Most notably, these locations
 //! are passed around throughout the parser and are stored in each
 //! Ast node, along with several structs in the ModuleCache.
+use std::cmp::{max, min};
+use std::convert::TryInto;
+use std::ops::RangeInclusive;
 use std::path::Path;
 
+use unicode_width::UnicodeWidthStr;
+
 /// A given Position in a file. These are usually used as
 /// start positions for a Location struct.
 ///
@@ -36,6 +41,41 @@ impl Position {
     }
 }
 
+/// Returns the Position of the given byte offset into `source`. Offsets past the
+/// end of `source` are clamped to the position just after its last character.
+pub fn offset_to_position(source: &str, offset: usize) -> Position {
+    let mut position = Position::begin();
+
+    for (i, c) in source.char_indices() {
+        if i >= offset {
+            break;
+        }
+        position.advance(c.len_utf8(), c == '\n');
+    }
+
+    position
+}
+
+/// Returns the byte offset into `source` of the given Position. This is the inverse
+/// of `offset_to_position` and ignores the Position's index field. A column may be
+/// one past the last character of a line, but None is returned for a line or
+/// column which is otherwise out of range.
+pub fn position_to_offset(source: &str, position: Position) -> Option<usize> {
+    let line_index = (position.line as usize).checked_sub(1)?;
+    let column_index = (position.column as usize).checked_sub(1)?;
+
+    let mut line_start = 0;
+    for _ in 0..line_index {
+        line_start += source[line_start..].find('\n')? + 1;
+    }
+
+    let line = &source[line_start..];
+    let line = line.split('\n').next().unwrap_or(line);
+
+    let mut offsets = line.char_indices().map(|(offset, _)| offset).chain(std::iter::once(line.len()));
+    offsets.nth(column_index).map(|offset| line_start + offset)
+}
+
 /// An ending position. Error reporting doesn't need to report
 /// the ending line/column of an error so it isn't stored here.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -58,6 +98,9 @@ pub struct File<'c> {
     pub contents: &'c str,
 }
 
+/// The filename of locations in generated code, see `Location::dummy`
+const DUMMY_FILENAME: &str = "<generated>";
+
 /// A source location for a given Ast node or other construct.
 /// The 'c lifetime refers to the ModuleCache which stores
 /// the file paths.
@@ -96,21 +139,272 @@ impl<'c> Location<'c> {
         Location::new(Path::new("stdlib/prelude.an"), start, end)
     }
 
-    pub fn length(&self) -> usize {
+    /// Returns a location for code generated by the compiler, such as by desugaring, that
+    /// does not correspond to any source code. Messages pointing to this location are
+    /// shown without any source since there is no file to read it from.
+    pub fn dummy() -> Location<'c> {
+        let start = Position { index: 0, line: 0, column: 0 };
+        Location::new(Path::new(DUMMY_FILENAME), start, EndPosition::new(0))
+    }
+
+    /// True if this is the location of generated code, see `Location::dummy`
+    pub fn is_dummy(&self) -> bool {
+        self.filename == Path::new(DUMMY_FILENAME)
+    }
+
+    /// The number of bytes this Location spans. Use this when slicing the source.
+    pub fn byte_length(&self) -> usize {
         self.end.index - self.start.index
     }
 
+    /// The number of columns this Location spans when displayed, given the contents of
+    /// its file. This differs from the byte length for any non-ASCII source, e.g. `é`
+    /// is two bytes wide but occupies one column while `名` is three bytes and two columns.
+    pub fn column_length(&self, file_contents: &str) -> usize {
+        let end = min(self.end.index, file_contents.len());
+        let start = min(self.start.index, end);
+        file_contents.get(start..end).map_or(0, |text| text.width())
+    }
+
     /// Unify the two Locations, returning a new Location that starts at the minimum
     /// of both starting points and ends at the maximum of both end points.
+    /// The order of the two Locations does not matter. Both must be in the same file.
     pub fn union(&self, other: Location<'c>) -> Location<'c> {
+        debug_assert_eq!(self.filename, other.filename, "Location::union called on locations in different files");
         let start = if self.start.index < other.start.index { self.start } else { other.start };
         let end = if self.end.index < other.end.index { other.end } else { self.end };
 
         Location { filename: self.filename, start, end }
     }
+
+    /// Returns this Location in a different file, keeping the same positions. This is
+    /// for locations of generated code which should point into the file it came from.
+    pub fn with_filename(self, filename: &'c Path) -> Location<'c> {
+        Location { filename, ..self }
+    }
+
+    /// True if the given 1-based line and column fall within this Location. This may span
+    /// several lines, in which case any column of a line between the first and last is
+    /// contained. The end is exclusive so a zero-length Location contains no positions.
+    /// Since only the index of the end is stored, its line and column are found from the
+    /// contents of this Location's file.
+    pub fn contains(&self, line: u32, column: u16) -> bool {
+        let end = super::end_line_and_column_of(self);
+        (self.start.line, self.start.column) <= (line, column) && (line, column) < end
+    }
+
+    /// The 1-based lines this Location touches, including both its first and last line.
+    /// As with `contains`, the last line is found from the contents of this Location's file.
+    pub fn line_range(&self) -> RangeInclusive<u32> {
+        let (end_line, _) = super::end_line_and_column_of(self);
+        self.start.line..=max(self.start.line, end_line)
+    }
+
+    /// True if this Location and `other` are in the same file and share at least one byte
+    pub fn overlaps(&self, other: &Location) -> bool {
+        self.filename == other.filename && self.start.index < other.end.index && other.start.index < self.end.index
+    }
+
+    /// Convert this Location into a JSON object containing its file, start position, and end
+    /// index. Unlike the locations in the JSON of an ErrorMessage, which are meant for display,
+    /// this keeps each field exactly so that `from_json` can recreate the same Location.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "file": self.filename.to_string_lossy(),
+            "start": { "index": self.start.index, "line": self.start.line, "column": self.start.column },
+            "end": { "index": self.end.index },
+        })
+    }
+
+    /// Recreate a Location from the JSON returned by `to_json`. Since a Location only borrows the
+    /// path of its file, `find_file` is given the path stored in the JSON and should return the
+    /// same path from wherever it is kept, such as the ModuleCache. Returns None if the JSON
+    /// is malformed or `find_file` returns None.
+    pub fn from_json(
+        json: &serde_json::Value, find_file: impl FnOnce(&Path) -> Option<&'c Path>,
+    ) -> Option<Location<'c>> {
+        let filename = find_file(Path::new(json["file"].as_str()?))?;
+
+        let start = &json["start"];
+        let start = Position {
+            index: start["index"].as_u64()?.try_into().ok()?,
+            line: start["line"].as_u64()?.try_into().ok()?,
+            column: start["column"].as_u64()?.try_into().ok()?,
+        };
+
+        let end = EndPosition::new(json["end"]["index"].as_u64()?.try_into().ok()?);
+        Some(Location::new(filename, start, end))
+    }
 }
 
 /// A trait representing anything that has a Location
 pub trait Locatable<'a> {
     fn locate(&self) -> Location<'a>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(filename: &'static str, index: usize, line: u32, column: u16, end: usize) -> Location<'static> {
+        Location::new(Path::new(filename), Position { index, line, column }, EndPosition::new(end))
+    }
+
+    #[test]
+    fn union_spans_both_locations_in_either_order() {
+        let first = location("a.an", 0, 1, 1, 3);
+        let second = location("a.an", 6, 2, 1, 9);
+
+        assert_eq!(first.union(second), location("a.an", 0, 1, 1, 9));
+        assert_eq!(second.union(first), location("a.an", 0, 1, 1, 9));
+        assert_eq!(first.union(first), first);
+    }
+
+    #[test]
+    #[should_panic(expected = "Location::union called on locations in different files")]
+    fn union_across_files_panics() {
+        let first = location("a.an", 4, 1, 5, 6);
+        let second = location("b.an", 0, 1, 1, 2);
+        first.union(second);
+    }
+
+    fn position(index: usize, line: u32, column: u16) -> Position {
+        Position { index, line, column }
+    }
+
+    #[test]
+    fn offset_to_position_counts_lines_and_characters() {
+        let source = "ab\nc\u{e9}d";
+        assert_eq!(offset_to_position(source, 0), position(0, 1, 1));
+        assert_eq!(offset_to_position(source, 1), position(1, 1, 2));
+        assert_eq!(offset_to_position(source, 3), position(3, 2, 1));
+
+        // `é` is two bytes but a single column
+        assert_eq!(offset_to_position(source, 6), position(6, 2, 3));
+
+        // The `\r` of a CRLF line ending is the last character of its line
+        assert_eq!(offset_to_position("ab\r\ncd", 2), position(2, 1, 3));
+        assert_eq!(offset_to_position("ab\r\ncd", 4), position(4, 2, 1));
+    }
+
+    #[test]
+    fn offset_to_position_clamps_to_the_end_of_the_source() {
+        let source = "ab\ncd";
+        assert_eq!(offset_to_position(source, source.len()), position(5, 2, 3));
+        assert_eq!(offset_to_position(source, 100), position(5, 2, 3));
+        assert_eq!(offset_to_position("ab\n", 3), position(3, 2, 1));
+        assert_eq!(offset_to_position("", 0), Position::begin());
+    }
+
+    #[test]
+    fn position_to_offset_is_the_inverse_of_offset_to_position() {
+        for source in ["ab\nc\u{e9}d", "ab\r\ncd\r\n", "\n\n", ""] {
+            for offset in (0..=source.len()).filter(|offset| source.is_char_boundary(*offset)) {
+                assert_eq!(position_to_offset(source, offset_to_position(source, offset)), Some(offset));
+            }
+        }
+    }
+
+    #[test]
+    fn position_to_offset_rejects_positions_outside_the_source() {
+        let source = "ab\ncd";
+        assert_eq!(position_to_offset(source, position(0, 1, 3)), Some(2));
+        assert_eq!(position_to_offset(source, position(0, 1, 4)), None);
+        assert_eq!(position_to_offset(source, position(0, 3, 1)), None);
+        assert_eq!(position_to_offset(source, position(0, 0, 1)), None);
+        assert_eq!(position_to_offset(source, position(0, 1, 0)), None);
+    }
+
+    #[test]
+    fn contains_positions_within_a_multi_line_span() {
+        crate::error::cache_source(Path::new("contains.an"), "ab\ncde\nfg");
+        let span = location("contains.an", 1, 1, 2, 8);
+
+        assert!(span.contains(1, 2));
+        assert!(span.contains(2, 10));
+        assert!(span.contains(3, 1));
+        assert!(!span.contains(1, 1));
+        assert!(!span.contains(3, 2));
+        assert!(!location("contains.an", 1, 1, 2, 1).contains(1, 2));
+    }
+
+    #[test]
+    fn overlaps_only_locations_sharing_a_byte() {
+        let span = location("a.an", 2, 1, 3, 5);
+
+        assert!(span.overlaps(&location("a.an", 4, 1, 5, 8)));
+        assert!(span.overlaps(&location("a.an", 0, 1, 1, 10)));
+        assert!(!span.overlaps(&location("a.an", 5, 1, 6, 8)));
+        assert!(!span.overlaps(&location("b.an", 2, 1, 3, 5)));
+    }
+
+    #[test]
+    fn line_range_includes_the_first_and_last_lines() {
+        crate::error::cache_source(Path::new("line_range.an"), "ab\ncde\nfg\n");
+
+        assert_eq!(location("line_range.an", 1, 1, 2, 8).line_range(), 1..=3);
+        assert_eq!(location("line_range.an", 3, 2, 1, 5).line_range(), 2..=2);
+        assert_eq!(location("line_range.an", 3, 2, 1, 3).line_range(), 2..=2);
+    }
+
+    #[test]
+    fn from_json_recreates_the_location_given_to_to_json() {
+        let path = Path::new("dir/json.an");
+        let original = Location::new(path, position(40, 3, 7), EndPosition::new(52));
+
+        let json = original.to_json();
+        assert_eq!(from_json_of(&json, path), Some(original));
+
+        // A compact JSON string also round-trips, as would one saved to disk
+        let json = serde_json::from_str(&json.to_string()).unwrap();
+        assert_eq!(from_json_of(&json, path), Some(original));
+    }
+
+    #[test]
+    fn from_json_rejects_malformed_json() {
+        let path = Path::new("json.an");
+        let mut json = location("json.an", 0, 1, 1, 2).to_json();
+
+        assert_eq!(Location::from_json(&json, |_| None), None);
+
+        json["start"]["column"] = serde_json::json!(100_000);
+        assert_eq!(from_json_of(&json, path), None);
+
+        json["start"] = serde_json::json!("1:1");
+        assert_eq!(from_json_of(&json, path), None);
+    }
+
+    /// Recreate a Location from JSON whose file is expected to be `path`
+    fn from_json_of(json: &serde_json::Value, path: &'static Path) -> Option<Location<'static>> {
+        Location::from_json(json, |file| {
+            assert_eq!(file, path);
+            Some(path)
+        })
+    }
+
+    #[test]
+    fn with_filename_keeps_the_same_positions() {
+        let original = location("generated.an", 4, 2, 3, 9);
+        let moved = original.with_filename(Path::new("macro.an"));
+
+        assert_eq!(moved.filename, Path::new("macro.an"));
+        assert_eq!((moved.start, moved.end), (original.start, original.end));
+        assert_eq!(moved.byte_length(), 5);
+    }
+
+    #[test]
+    fn column_length_counts_display_columns_rather_than_bytes() {
+        let source = "h\u{e9}llo \u{540d}";
+        let word = location("column_length.an", 0, 1, 1, 6);
+        assert_eq!(word.byte_length(), 6);
+        assert_eq!(word.column_length(source), 5);
+
+        // A wide character is three bytes but two columns
+        let wide = location("column_length.an", 7, 1, 7, 10);
+        assert_eq!((wide.byte_length(), wide.column_length(source)), (3, 2));
+
+        // Locations past the end of the contents, e.g. from a stale file, are clamped
+        assert_eq!(location("column_length.an", 7, 1, 7, 100).column_length(source), 2);
+        assert_eq!(location("column_length.an", 50, 1, 51, 100).column_length(source), 0);
+    }
+}
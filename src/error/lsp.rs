@@ -0,0 +1,149 @@
+//! lsp.rs - Conversions from ErrorMessages into the Diagnostic shape
+//! used by the language server protocol. These mirror the structs in the
+//! LSP specification so that a language server built on top of ante's
+//! front-end can forward them to clients without further translation.
+use super::{source_for, ErrorMessage, ErrorType};
+
+/// A zero-based position within a file. Unlike `location::Position`,
+/// both the line and character offsets start from 0, and characters
+/// are counted in UTF-16 code units.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LspPosition {
+    pub line: u32,
+    pub character: u32,
+}
+
+/// A half-open range of positions: `start` is inclusive and `end` exclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LspRange {
+    pub start: LspPosition,
+    pub end: LspPosition,
+}
+
+/// Numeric severities as defined by the LSP specification.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LspSeverity {
+    Error = 1,
+    Warning = 2,
+    Information = 3,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LspDiagnostic {
+    pub range: LspRange,
+    pub severity: LspSeverity,
+    pub code: Option<String>,
+    pub source: &'static str,
+    pub message: String,
+}
+
+impl<'a> ErrorMessage<'a> {
+    pub fn to_lsp_diagnostic(&self) -> LspDiagnostic {
+        let (end_line, end_column) = self.end_line_and_column();
+        let contents = source_for(self.location.filename).ok();
+        let position = |line: u32, column: u16| LspPosition {
+            line: line.saturating_sub(1),
+            character: utf16_character(contents.as_deref(), line, column),
+        };
+
+        // Locations are 1-based, except for builtins which are at line 0, column 0.
+        let start = position(self.location.start.line, self.location.start.column);
+
+        // A zero-length Location has an end index equal to its start index, so this
+        // naturally produces an empty range rather than one spanning a character.
+        let end = if self.location.byte_length() == 0 { start } else { position(end_line, end_column) };
+
+        let severity = match self.severity() {
+            ErrorType::Error => LspSeverity::Error,
+            ErrorType::Warning => LspSeverity::Warning,
+            ErrorType::Note => LspSeverity::Information,
+        };
+
+        LspDiagnostic {
+            range: LspRange { start, end },
+            severity,
+            code: self.code.map(|code| code.code().to_owned()),
+            source: "ante",
+            message: self.plain_msg().into_owned(),
+        }
+    }
+}
+
+/// The LSP counts the characters of a line in UTF-16 code units, so convert the given 1-based
+/// column, which counts chars, into the number of code units before it on its line. Without
+/// the file's contents each char is assumed to be a single code unit.
+fn utf16_character(contents: Option<&str>, line: u32, column: u16) -> u32 {
+    let chars_before = column.saturating_sub(1) as usize;
+    let line = line.checked_sub(1).and_then(|line| contents?.lines().nth(line as usize));
+
+    match line {
+        // Any columns past the end of the line, such as that of its newline, are one unit each
+        Some(line) => {
+            let units: usize = line.chars().take(chars_before).map(char::len_utf16).sum();
+            (units + chars_before.saturating_sub(line.chars().count())) as u32
+        },
+        None => chars_before as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::location::{EndPosition, Location, Position};
+    use crate::error::{cache_source, ErrorCode};
+    use std::path::Path;
+
+    fn location(path: &'static Path, index: usize, line: u32, column: u16, end: usize) -> Location<'static> {
+        Location::new(path, Position { index, line, column }, EndPosition::new(end))
+    }
+
+    fn position(line: u32, character: u32) -> LspPosition {
+        LspPosition { line, character }
+    }
+
+    #[test]
+    fn positions_are_zero_based_and_the_end_is_exclusive() {
+        let path = Path::new("lsp_range.an");
+        cache_source(path, "a = 1\nbcd = 2\n");
+
+        let message = ErrorMessage::error("error", location(path, 6, 2, 1, 9)).with_code(ErrorCode::AssignToImmutable);
+        let diagnostic = message.to_lsp_diagnostic();
+
+        assert_eq!(diagnostic.range, LspRange { start: position(1, 0), end: position(1, 3) });
+        assert_eq!(diagnostic.severity, LspSeverity::Error);
+        assert_eq!(diagnostic.code.as_deref(), Some("E0018"));
+        assert_eq!(diagnostic.source, "ante");
+        assert_eq!(diagnostic.message, "error");
+    }
+
+    #[test]
+    fn zero_length_locations_produce_empty_ranges() {
+        let path = Path::new("lsp_empty.an");
+        cache_source(path, "a = 1\n");
+
+        let diagnostic = ErrorMessage::note("note", location(path, 4, 1, 5, 4)).to_lsp_diagnostic();
+        assert_eq!(diagnostic.range, LspRange { start: position(0, 4), end: position(0, 4) });
+        assert_eq!(diagnostic.severity, LspSeverity::Information);
+        assert_eq!(diagnostic.code, None);
+    }
+
+    #[test]
+    fn builtin_locations_start_at_the_beginning_of_the_file() {
+        let path = Path::new("lsp_builtin.an");
+        cache_source(path, "");
+
+        let diagnostic = ErrorMessage::warning("warning", location(path, 0, 0, 0, 0)).to_lsp_diagnostic();
+        assert_eq!(diagnostic.range, LspRange { start: position(0, 0), end: position(0, 0) });
+        assert_eq!(diagnostic.severity, LspSeverity::Warning);
+    }
+
+    #[test]
+    fn characters_are_counted_in_utf16_code_units() {
+        let path = Path::new("lsp_utf16.an");
+        // The emoji is outside the Basic Multilingual Plane so it is two UTF-16 code units
+        cache_source(path, "s = \"\u{1f600}\" ++ xs\n");
+
+        let diagnostic = ErrorMessage::error("error", location(path, 14, 1, 12, 16)).to_lsp_diagnostic();
+        assert_eq!(diagnostic.range, LspRange { start: position(0, 12), end: position(0, 14) });
+    }
+}
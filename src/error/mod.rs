@@ -1,40 +1,109 @@
 //! error/mod.rs - Defines the error, warning, and note macros
 //! used to issue compiler errors. There is also an ErrorMessage type
-//! for storing messages that may be issued later. Note that all issuing
-//! an error does is print it to stderr and update the global ERROR_COUNT.
+//! for storing messages that may be issued later, and a DiagnosticsCollector
+//! for buffering several of them. Note that unless a collector is given,
+//! all issuing an error does is print it to stderr and update the MESSAGE_COUNTS.
+//!
+//! Errors may optionally be given an ErrorCode by prefixing the macro's
+//! arguments with the code's name, e.g. `error!(NotFound; location, "...")`.
 //!
 //! Compiler passes are expected to continue even after issuing errors so
-//! that as many can be issued as possible. A possible future improvement
-//! would be to implement poisoning so that repeated errors are hidden.
+//! that as many can be issued as possible. To avoid repeating errors, the
+//! type checker poisons the types of erroneous expressions with Type::Error.
+pub mod codes;
+pub mod collector;
 pub mod location;
+pub mod lsp;
+pub mod theme;
+use crate::error::codes::ErrorCode;
 use crate::error::location::Location;
+use crate::error::theme::Theme;
 
 use colored::ColoredString;
 use colored::*;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
 use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::fmt::{Display, Formatter};
 use std::fs::File;
-use std::io::{BufReader, Read};
-use std::path::Path;
+use std::io::{BufReader, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf, Prefix};
+use std::rc::Rc;
 use std::sync::atomic::Ordering::SeqCst;
 use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 static COLORED_OUTPUT: AtomicBool = AtomicBool::new(true);
 
-static ERROR_COUNT: AtomicUsize = AtomicUsize::new(0);
+static ALWAYS_UNDERLINE: AtomicBool = AtomicBool::new(false);
+
+static HYPERLINKS: AtomicBool = AtomicBool::new(false);
+
+static DIFF_TYPES: AtomicBool = AtomicBool::new(false);
+
+static WARNINGS_AS_ERRORS: AtomicBool = AtomicBool::new(false);
+
+static MAX_TYPE_DEPTH: RwLock<Option<usize>> = RwLock::new(None);
+
+static MAX_LINE_WIDTH: RwLock<Option<usize>> = RwLock::new(None);
+
+static TAB_WIDTH: AtomicUsize = AtomicUsize::new(4);
+
+static CONTEXT_LINES: AtomicUsize = AtomicUsize::new(1);
+
+static MESSAGE_FORMAT: RwLock<MessageFormat> = RwLock::new(MessageFormat::Human);
+
+static CARET_STYLE: RwLock<CaretStyle> = RwLock::new(CaretStyle::Caret);
+
+static INDICATOR_CHARS: RwLock<IndicatorChars> = RwLock::new(IndicatorChars { caret: '^', underline: '~' });
+
+static MIN_SEVERITY: RwLock<ErrorType> = RwLock::new(ErrorType::Note);
+
+static PATH_ROOT: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+static THEME: RwLock<Theme> = RwLock::new(Theme::colored());
+
+thread_local! {
+    /// The number of messages of each severity issued on the current thread. This is
+    /// thread-local so that compilations on separate threads don't count each other's
+    /// messages. Use `ModuleCache::message_counts` for the messages of a single compilation.
+    static MESSAGE_COUNTS: Cell<MessageCounts> = Cell::new(MessageCounts::default());
+
+    /// The message counts of the compilation in progress on the current thread, if any.
+    /// Each issued message is counted here as well as in MESSAGE_COUNTS, see `count_messages_in`.
+    static COMPILATION_COUNTS: RefCell<Weak<Mutex<MessageCounts>>> = const { RefCell::new(Weak::new()) };
+
+    /// The contents of each file a message may point into. Files are added here as
+    /// they are read by the compiler so that issuing a message doesn't need to re-read
+    /// the entire file from disk each time.
+    static SOURCE_CACHE: RefCell<HashMap<PathBuf, Rc<str>>> = RefCell::new(HashMap::new());
+}
 
 /// Return an error which may be issued later
 macro_rules! make_error {
+    ( $code:ident ; $location:expr , $fmt_string:expr $( , $($msg:tt)* )? ) => ({
+        make_error!($location, $fmt_string $( , $($msg)* )?).with_code($crate::error::codes::ErrorCode::$code)
+    });
     ( $location:expr , $fmt_string:expr $( , $($msg:tt)* )? ) => ({
         let message = format!($fmt_string $( , $($msg)* )? );
         $crate::error::ErrorMessage::error(&message[..], $location)
     });
 }
 
-/// Issue an error message to stderr and increment the error count
+/// Issue an error message to stderr and increment the error count,
+/// or push it to the given DiagnosticsCollector if one is given first.
 macro_rules! error {
+    ( $collector:expr => $($args:tt)* ) => ({
+        $collector.push(make_error!($($args)*));
+    });
+    ( $code:ident ; $location:expr , $fmt_string:expr $( , $($msg:tt)* )? ) => ({
+        make_error!($code; $location, $fmt_string $( , $($msg)* )?).issue();
+    });
     ( $location:expr , $fmt_string:expr $( , $($msg:tt)* )? ) => ({
-        eprintln!("{}", make_error!($location, $fmt_string $( , $($msg)* )?));
+        make_error!($location, $fmt_string $( , $($msg)* )?).issue();
     });
 }
 
@@ -46,10 +115,13 @@ macro_rules! make_warning {
     });
 }
 
-/// Issues a warning to stderr
+/// Issues a warning to stderr, or pushes it to the given DiagnosticsCollector
 macro_rules! warning {
+    ( $collector:expr => $($args:tt)* ) => ({
+        $collector.push(make_warning!($($args)*));
+    });
     ( $location:expr , $fmt_string:expr $( , $($msg:tt)* )? ) => ({
-        eprintln!("{}", make_warning!($location, $fmt_string $( , $($msg)* )?));
+        make_warning!($location, $fmt_string $( , $($msg)* )?).issue();
     });
 }
 
@@ -61,26 +133,121 @@ macro_rules! make_note {
     });
 }
 
-/// Issues a note to stderr
+/// Issues a note to stderr, or pushes it to the given DiagnosticsCollector
 macro_rules! note {
+    ( $collector:expr => $($args:tt)* ) => ({
+        $collector.push(make_note!($($args)*));
+    });
     ( $location:expr , $fmt_string:expr $( , $($msg:tt)* )? ) => ({
-        eprintln!("{}", make_note!($location, $fmt_string $( , $($msg)* )?));
+        make_note!($location, $fmt_string $( , $($msg)* )?).issue();
     });
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// The severity of a message. These are ordered from most to least severe.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
 pub enum ErrorType {
     Error,
     Warning,
     Note,
 }
 
+impl ErrorType {
+    /// The lowercase name of this severity, as used in the message header
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorType::Error => "error",
+            ErrorType::Warning => "warning",
+            ErrorType::Note => "note",
+        }
+    }
+}
+
+/// The format ErrorMessages are displayed in when issued.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, clap::ValueEnum)]
+pub enum MessageFormat {
+    /// Human-readable messages along with the offending source line
+    Human,
+
+    /// One JSON object per message, intended for editors and other tooling. Each object is
+    /// written on its own line as soon as its message is issued, so the output can be read
+    /// as a stream of newline-delimited JSON while compilation is still running.
+    Json,
+
+    /// Each message on a single line without its source, for searching with tools like grep
+    Short,
+
+    /// The `path:line:column: severity: message` format of GNU tools, which editors such as
+    /// vim and emacs can parse to jump to each location
+    Gnu,
+
+    /// One JSON object per message in the `compiler-message` format of `cargo --message-format json`,
+    /// so that tools which already parse cargo's output can parse ante's as well
+    CargoJson,
+}
+
+/// How the indicator line beneath highlighted source is drawn. The characters used
+/// for each style default to `^` and `~` and can be changed with `indicator_chars`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, clap::ValueEnum)]
+pub enum CaretStyle {
+    /// A `^` beneath each column, e.g. `^^^^`
+    Caret,
+
+    /// A `^` beneath the first column and a `~` beneath the rest, e.g. `^~~~`
+    Tilde,
+}
+
+/// The characters the indicator line is drawn with, see `indicator_chars`
+#[derive(Debug, Copy, Clone)]
+struct IndicatorChars {
+    caret: char,
+    underline: char,
+}
+
+impl CaretStyle {
+    /// Returns the indicator for a highlighted part of a line which is `width` columns wide
+    fn indicator(self, width: usize) -> String {
+        let IndicatorChars { caret, underline } = *INDICATOR_CHARS.read().unwrap();
+        match self {
+            CaretStyle::Caret => caret.to_string().repeat(width),
+            CaretStyle::Tilde => {
+                let rest = underline.to_string().repeat(width.saturating_sub(1));
+                format!("{}{}", caret, rest)
+            },
+        }
+    }
+}
+
 /// An error (or warning/note) message to be printed out on screen.
 #[derive(Debug, PartialEq, Eq)]
 pub struct ErrorMessage<'a> {
     msg: ColoredString,
     error_type: ErrorType,
+    code: Option<ErrorCode>,
     location: Location<'a>,
+
+    /// Boxed since most messages have no details and ErrorMessages are often returned
+    /// as the error of a Result, which should be kept small.
+    details: Box<MessageDetails<'a>>,
+}
+
+/// Everything shown for an ErrorMessage beneath its own location
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MessageDetails<'a> {
+    /// Secondary locations related to this message, each with a short description
+    labels: Vec<(Location<'a>, String)>,
+
+    /// Additional context explaining this message, shown after its labels
+    notes: Vec<String>,
+    suggestion: Option<Suggestion<'a>>,
+}
+
+/// A suggested fix shown beneath an ErrorMessage. Applying the fix
+/// means replacing the source at `location` with `replacement`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Suggestion<'a> {
+    pub message: String,
+    pub location: Location<'a>,
+    pub replacement: String,
 }
 
 /// ErrorMessages are ordered so we can issue them in a
@@ -99,126 +266,1448 @@ impl<'a> PartialOrd for ErrorMessage<'a> {
 }
 
 impl<'a> ErrorMessage<'a> {
+    fn new(msg: ColoredString, location: Location<'a>, error_type: ErrorType) -> ErrorMessage<'a> {
+        ErrorMessage { msg, location, error_type, code: None, details: Box::default() }
+    }
+
     pub fn error<T: Into<ColoredString>>(msg: T, location: Location<'a>) -> ErrorMessage<'a> {
-        ErrorMessage { msg: msg.into(), location, error_type: ErrorType::Error }
+        ErrorMessage::new(msg.into(), location, ErrorType::Error)
     }
 
     pub fn warning<T: Into<ColoredString>>(msg: T, location: Location<'a>) -> ErrorMessage<'a> {
-        ErrorMessage { msg: msg.into(), location, error_type: ErrorType::Warning }
+        ErrorMessage::new(msg.into(), location, ErrorType::Warning)
     }
 
     pub fn note<T: Into<ColoredString>>(msg: T, location: Location<'a>) -> ErrorMessage<'a> {
-        ErrorMessage { msg: msg.into(), location, error_type: ErrorType::Note }
+        ErrorMessage::new(msg.into(), location, ErrorType::Note)
+    }
+
+    /// Attach the given ErrorCode to this message
+    pub fn with_code(mut self, code: ErrorCode) -> ErrorMessage<'a> {
+        self.code = Some(code);
+        self
     }
 
-    fn marker(&self) -> ColoredString {
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.code
+    }
+
+    /// The location this message points to, not including any of its labels
+    pub fn location(&self) -> Location<'a> {
+        self.location
+    }
+
+    /// Point out a related location, shown beneath the message's own location
+    pub fn with_label(mut self, location: Location<'a>, label: impl Into<String>) -> ErrorMessage<'a> {
+        self.details.labels.push((location, label.into()));
+        self
+    }
+
+    /// Add a note explaining this message. Notes are shown in the order they are added,
+    /// after any labels and before any suggestion.
+    pub fn with_note(mut self, note: impl Into<String>) -> ErrorMessage<'a> {
+        self.details.notes.push(note.into());
+        self
+    }
+
+    /// Suggest replacing the source at the given location with `replacement` to fix this message
+    pub fn with_suggestion(
+        mut self, message: impl Into<String>, location: Location<'a>, replacement: impl Into<String>,
+    ) -> ErrorMessage<'a> {
+        self.details.suggestion =
+            Some(Suggestion { message: message.into(), location, replacement: replacement.into() });
+        self
+    }
+
+    /// Write this message to the given output followed by a newline, e.g. to a file or to an
+    /// in-memory buffer rather than stderr. Unlike only displaying it, this counts towards the error count.
+    /// Nothing is written if the message is less severe than those set to be shown by `min_severity`.
+    pub fn emit(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        if !self.is_shown() {
+            return Ok(());
+        }
+        // An error isn't considered an error until it is actually issued.
+        // That's why MESSAGE_COUNTS is incremented here and not when ErrorMessage is constructed.
+        self.count_issued();
+        writeln!(out, "{}", self)
+    }
+
+    /// Issue this message to stderr, see `emit`
+    pub fn issue(&self) {
+        self.emit(&mut std::io::stderr().lock()).unwrap();
+    }
+
+    /// The severity this message is issued with. This is the ErrorType it was
+    /// created with, except that warnings are promoted to errors with `--warnings-as-errors`.
+    pub fn severity(&self) -> ErrorType {
         match self.error_type {
-            ErrorType::Error => self.color("error:"),
-            ErrorType::Warning => self.color("warning:"),
-            ErrorType::Note => self.color("note:"),
+            ErrorType::Warning if WARNINGS_AS_ERRORS.load(SeqCst) => ErrorType::Error,
+            other => other,
         }
     }
 
-    /// Color the given string in either the error, warning, or note color
-    fn color(&self, msg: &str) -> ColoredString {
-        match (COLORED_OUTPUT.load(SeqCst), self.error_type) {
-            (false, _) => msg.normal(),
-            (_, ErrorType::Error) => msg.red(),
-            (_, ErrorType::Warning) => msg.yellow(),
-            (_, ErrorType::Note) => msg.purple(),
+    /// True if this message counts towards the error count when issued
+    pub fn is_error(&self) -> bool {
+        self.severity() == ErrorType::Error
+    }
+
+    /// Count this message towards the number issued of its severity
+    fn count_issued(&self) {
+        MESSAGE_COUNTS.with(|counts| {
+            let mut new_counts = counts.get();
+            new_counts.count(self.severity());
+            counts.set(new_counts);
+        });
+
+        if let Some(counts) = COMPILATION_COUNTS.with(|counts| counts.borrow().upgrade()) {
+            counts.lock().unwrap().count(self.severity());
         }
     }
+
+    /// False if this message is less severe than those set to be shown by `min_severity`
+    pub fn is_shown(&self) -> bool {
+        self.severity() <= *MIN_SEVERITY.read().unwrap()
+    }
+
+    fn marker(&self, colored: bool) -> ColoredString {
+        match self.code {
+            Some(code) => self.color(&format!("{}[{}]:", self.severity().name(), code), colored),
+            None => self.color(&format!("{}:", self.severity().name()), colored),
+        }
+    }
+
+    /// Color the given string in either the error, warning, or note color
+    fn color(&self, msg: &str, colored: bool) -> ColoredString {
+        severity_color(self.severity(), msg, colored)
+    }
+}
+
+/// Color the given string in the color used for messages of the given severity,
+/// or leave it uncolored if `colored` is false
+fn severity_color(severity: ErrorType, msg: &str, colored: bool) -> ColoredString {
+    let theme = THEME.read().unwrap();
+    match (colored, severity) {
+        (false, _) => msg.normal(),
+        (_, ErrorType::Error) => theme.error.paint(msg),
+        (_, ErrorType::Warning) => theme.warning.paint(msg),
+        (_, ErrorType::Note) => theme.note.paint(msg),
+    }
+}
+
+/// Write a final line summarizing the number of errors and warnings issued, e.g.
+/// `error: aborting due to 2 previous errors; 1 warning emitted`. Nothing is written if
+/// neither were issued or if messages are in a machine-readable format.
+pub fn emit_summary(counts: MessageCounts, out: &mut dyn Write) -> std::io::Result<()> {
+    if *MESSAGE_FORMAT.read().unwrap() != MessageFormat::Human {
+        return Ok(());
+    }
+
+    let colored = COLORED_OUTPUT.load(SeqCst);
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    let warnings = format!("{} warning{} emitted", counts.warnings, plural(counts.warnings));
+
+    match (counts.errors, counts.warnings) {
+        (0, 0) => Ok(()),
+        (0, _) => writeln!(out, "{} {}", severity_color(ErrorType::Warning, "warning:", colored), warnings),
+        (errors, warning_count) => {
+            let marker = severity_color(ErrorType::Error, "error:", colored);
+            write!(out, "{} aborting due to {} previous error{}", marker, errors, plural(errors))?;
+            if warning_count != 0 {
+                write!(out, "; {}", warnings)?;
+            }
+            writeln!(out)
+        },
+    }
 }
 
 /// Reads the given file, returning all of its contents
-fn read_file_or_panic(path: &Path) -> String {
-    let file = File::open(path).unwrap();
+fn read_file(path: &Path) -> std::io::Result<String> {
+    let file = File::open(path)?;
     let mut reader = BufReader::new(file);
     let mut contents = String::new();
-    reader.read_to_string(&mut contents).unwrap();
-    contents
+    reader.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// The Locations of blocks extend up until the token following them, so they can
+/// contain trailing blank lines and comments that aren't worth showing to users.
+fn trim_trailing_lines(span: &str) -> &str {
+    let mut span = span.trim_end();
+
+    while let Some(last_newline) = span.rfind('\n') {
+        if !span[last_newline + 1..].trim_start().starts_with("//") {
+            break;
+        }
+        span = span[..last_newline].trim_end();
+    }
+
+    span
+}
+
+/// Replace each tab in `text` with spaces up to the next tab stop, given
+/// that `text` starts `start_width` columns into the line.
+fn expand_tabs(text: &str, start_width: usize) -> String {
+    let tab_width = max(1, TAB_WIDTH.load(SeqCst));
+    let mut expanded = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if c == '\t' {
+            let width = start_width + expanded.width();
+            expanded.push_str(&" ".repeat(tab_width - width % tab_width));
+        } else {
+            expanded.push(c);
+        }
+    }
+
+    expanded
+}
+
+/// Remember the contents of the given file so that messages pointing into it
+/// can display its source without reading it again. The path need not exist on
+/// disk, so this is also how virtual files such as `<stdin>` are registered.
+/// Caching a file again replaces its previous contents, e.g. after it is edited.
+pub fn cache_source(path: &Path, contents: &str) {
+    SOURCE_CACHE.with(|cache| cache.borrow_mut().insert(path.to_owned(), contents.into()));
+}
+
+/// Forget the cached contents of the given file, e.g. once it has changed or is no longer
+/// open in a long-running process. Any later message pointing into it reads it from disk again.
+pub fn evict_source(path: &Path) {
+    SOURCE_CACHE.with(|cache| cache.borrow_mut().remove(path));
 }
 
-/// Sets whether error message output should be colored or not
+/// Returns whether the cached contents of the given file are the same as its contents on disk,
+/// so that a long-running process can tell when to cache it again. Returns None if the file
+/// is not cached or cannot be read, such as for virtual files.
+pub fn is_source_current(path: &Path) -> Option<bool> {
+    let cached = SOURCE_CACHE.with(|cache| cache.borrow().get(path).cloned())?;
+    let contents = read_file(path).ok()?;
+    Some(*cached == *contents)
+}
+
+/// Forget the cached contents of every file on the current thread, see `evict_source`
+pub fn clear_source_cache() {
+    SOURCE_CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
+/// Returns the contents of the given file, only reading it from disk
+/// if it has not already been cached.
+fn source_for(path: &Path) -> std::io::Result<Rc<str>> {
+    if let Some(contents) = SOURCE_CACHE.with(|cache| cache.borrow().get(path).cloned()) {
+        return Ok(contents);
+    }
+
+    let contents: Rc<str> = read_file(path)?.into();
+    SOURCE_CACHE.with(|cache| cache.borrow_mut().insert(path.to_owned(), contents.clone()));
+    Ok(contents)
+}
+
+/// Returns the 1-based line and column of the given byte index into `contents`
+fn line_and_column_of(contents: &str, index: usize) -> (u32, u16) {
+    let position = location::offset_to_position(contents, index);
+    (position.line, position.column)
+}
+
+/// Sets whether error message output should be colored or not.
+/// This also applies to any colored text within the messages themselves, such as types.
 pub fn color_output(should_color: bool) {
     COLORED_OUTPUT.store(should_color, SeqCst);
+    colored::control::set_override(should_color);
+}
+
+/// Returns whether messages should be colored when not otherwise specified.
+/// Setting ANTE_COLOR to `always` or `never` forces coloring on or off, taking precedence
+/// over NO_COLOR. When ANTE_COLOR is `auto` or unset, coloring follows <https://no-color.org>
+/// so that setting NO_COLOR to any value disables it. Coloring is also disabled when stderr
+/// is not a terminal since messages are printed there and escape codes are just noise when
+/// redirected to a file.
+pub fn should_color_by_default() -> bool {
+    let ante_color = std::env::var("ANTE_COLOR").ok();
+    let no_color = std::env::var_os("NO_COLOR");
+    color_by_default(ante_color.as_deref(), no_color.as_deref(), std::io::stderr().is_terminal())
+}
+
+/// The decision behind `should_color_by_default`, given the values of ANTE_COLOR
+/// and NO_COLOR (None if unset) and whether stderr is a terminal.
+fn color_by_default(ante_color: Option<&str>, no_color: Option<&OsStr>, stderr_is_terminal: bool) -> bool {
+    match ante_color {
+        Some("always") => true,
+        Some("never") => false,
+        _ => no_color.is_none() && stderr_is_terminal,
+    }
+}
+
+/// When set, the indicator line beneath highlighted source is drawn even when output is colored.
+/// This keeps locations visible if the colors are later stripped, e.g. when saving output to a file.
+pub fn always_underline(enabled: bool) {
+    ALWAYS_UNDERLINE.store(enabled, SeqCst);
+}
+
+/// When set, the location of each message is a link which opens its file when clicked in
+/// terminals supporting OSC 8 hyperlinks. Like colors, links are only output when colored
+/// output is enabled since they are escape codes which would otherwise be shown as noise.
+pub fn hyperlinks(enabled: bool) {
+    HYPERLINKS.store(enabled, SeqCst);
+}
+
+/// When set, messages about two mismatched types highlight only the parts of each type that differ
+pub fn diff_types(enabled: bool) {
+    DIFF_TYPES.store(enabled, SeqCst);
+}
+
+/// True if mismatched types should be shown with their differences highlighted, see `diff_types`
+pub fn should_diff_types() -> bool {
+    DIFF_TYPES.load(SeqCst)
+}
+
+/// Sets how deeply types may be nested within other types when displayed. Types nested any
+/// deeper are abbreviated as `...`, e.g. with a depth of 2 `Maybe (List (Maybe I32))` is shown
+/// as `Maybe (List ...)`. When this is None, types are always shown in full.
+pub fn max_type_depth(depth: Option<usize>) {
+    *MAX_TYPE_DEPTH.write().unwrap() = depth;
+}
+
+/// The maximum depth types are displayed to, see `max_type_depth`
+pub fn get_max_type_depth() -> Option<usize> {
+    *MAX_TYPE_DEPTH.read().unwrap()
+}
+
+/// When set, all subsequent warnings are issued as errors instead
+pub fn warnings_as_errors(enabled: bool) {
+    WARNINGS_AS_ERRORS.store(enabled, SeqCst);
+}
+
+/// Sets the directory paths in messages are shown relative to. Paths of files outside
+/// of this directory are shown unchanged. When this is None, every path is unchanged.
+pub fn path_root(root: Option<PathBuf>) {
+    *PATH_ROOT.write().unwrap() = root;
+}
+
+/// Sets the number of columns between each tab stop when displaying source lines
+pub fn tab_width(width: usize) {
+    TAB_WIDTH.store(width, SeqCst);
+}
+
+/// Sets the number of lines shown above and below the source pointed to by a message
+pub fn context_lines(lines: usize) {
+    CONTEXT_LINES.store(lines, SeqCst);
+}
+
+/// Sets the maximum number of columns of each source line shown in messages, not counting
+/// the line number gutter. Longer lines are shortened around the part of them a message points
+/// to, with `...` in place of the text removed. When this is None, lines are always shown in full.
+pub fn max_line_width(width: Option<usize>) {
+    *MAX_LINE_WIDTH.write().unwrap() = width;
+}
+
+/// Sets the format all subsequent ErrorMessages are displayed in.
+/// Machine-readable formats also disable coloring entirely so that
+/// no escape codes end up in the messages themselves.
+pub fn message_format(format: MessageFormat) {
+    if format != MessageFormat::Human {
+        color_output(false);
+    }
+    *MESSAGE_FORMAT.write().unwrap() = format;
+}
+
+/// Sets the characters used to draw the indicator line beneath highlighted source
+pub fn caret_style(style: CaretStyle) {
+    *CARET_STYLE.write().unwrap() = style;
+}
+
+/// Sets the characters used to draw the indicator line beneath highlighted source, for
+/// terminals or fonts which show `^` or `~` poorly. `caret` replaces `^` and `underline`
+/// replaces `~`. Each should be a character which is a single column wide.
+pub fn indicator_chars(caret: char, underline: char) {
+    *INDICATOR_CHARS.write().unwrap() = IndicatorChars { caret, underline };
+}
+
+/// Only issue messages at least as severe as the given severity. For example, with
+/// `ErrorType::Warning` no notes are issued. Defaults to `ErrorType::Note`, issuing every message.
+pub fn min_severity(severity: ErrorType) {
+    *MIN_SEVERITY.write().unwrap() = severity;
+}
+
+/// Sets the Theme used to color all subsequent messages when colored output is enabled
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap() = theme;
+}
+
+/// The number of errors, warnings, and notes issued
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct MessageCounts {
+    pub errors: usize,
+    pub warnings: usize,
+    pub notes: usize,
+}
+
+impl MessageCounts {
+    fn count(&mut self, severity: ErrorType) {
+        match severity {
+            ErrorType::Error => self.errors += 1,
+            ErrorType::Warning => self.warnings += 1,
+            ErrorType::Note => self.notes += 1,
+        }
+    }
+}
+
+impl std::ops::Sub for MessageCounts {
+    type Output = MessageCounts;
+
+    fn sub(self, other: MessageCounts) -> MessageCounts {
+        MessageCounts {
+            errors: self.errors - other.errors,
+            warnings: self.warnings - other.warnings,
+            notes: self.notes - other.notes,
+        }
+    }
 }
 
-pub fn get_error_count() -> usize {
-    ERROR_COUNT.load(SeqCst)
+/// The number of messages of each severity issued on the current thread
+pub fn get_message_counts() -> MessageCounts {
+    MESSAGE_COUNTS.with(Cell::get)
+}
+
+/// Count each message issued on the current thread from now on towards `counts` as well, until
+/// this is called again with different counts or `counts` is dropped. A ModuleCache calls this
+/// when it is created so that it only counts the messages of its own compilation.
+pub fn count_messages_in(counts: &Arc<Mutex<MessageCounts>>) {
+    COMPILATION_COUNTS.with(|current| *current.borrow_mut() = Arc::downgrade(counts));
 }
 
 /// Format the path in an OS-agnostic way. By default rust uses "/" on Unix
 /// and "\" on windows as the path separator. This makes testing more
 /// difficult and isn't needed for error reporting so we implement our own
 /// path-Displaying here that is roughly the same as printing Unix paths.
-fn os_agnostic_display_path(path: &Path) -> ColoredString {
+/// Paths within the directory set by `path_root` are shown relative to it.
+fn os_agnostic_display_path(path: &Path, colored: bool) -> ColoredString {
+    let root = PATH_ROOT.read().unwrap();
+    let path = match root.as_deref().and_then(|root| path.strip_prefix(root).ok()) {
+        Some(relative) if relative != Path::new("") => relative,
+        _ => path,
+    };
+
     let mut ret = String::new();
 
-    for (i, component) in path.components().enumerate() {
+    // Separators are only needed between two consecutive directory or file names
+    let mut needs_separator = false;
+
+    for component in path.components() {
         use std::path::Component;
 
         // Use / as the separator regardless of the host OS so
         // we can use the same tests for Linux/Mac/Windows
-        if i != 0 && ret != "/" {
+        let is_name = !matches!(component, Component::Prefix(_) | Component::RootDir);
+        if needs_separator && is_name {
             ret += "/";
         }
+        needs_separator = is_name;
 
-        ret += match component {
-            Component::CurDir => ".",
-            Component::Normal(s) => s.to_str().expect("Path contains invalid utf-8"),
-            Component::ParentDir => "..",
-            Component::Prefix(_) => "",
-            Component::RootDir => "/",
+        match component {
+            Component::CurDir => ret += ".",
+            Component::Normal(s) => ret += s.to_str().expect("Path contains invalid utf-8"),
+            Component::ParentDir => ret += "..",
+            Component::Prefix(prefix) => match display_drive(prefix.kind()) {
+                Some(drive) => ret += &drive,
+                None => ret += &prefix.as_os_str().to_string_lossy(),
+            },
+            Component::RootDir => ret += "/",
         }
     }
 
-    if COLORED_OUTPUT.load(SeqCst) {
-        ret.italic()
+    if colored {
+        THEME.read().unwrap().path.paint(&ret)
     } else {
         ret.normal()
     }
 }
 
+/// Returns the drive of a Windows path prefix as it is shown in messages, e.g. the `C:` of
+/// `C:/a/b`, so that the path still refers to the same file. Verbatim paths such as `\\?\C:\a`
+/// are shown the same as their usual form. Returns None for prefixes without a drive.
+fn display_drive(prefix: Prefix) -> Option<String> {
+    match prefix {
+        Prefix::Disk(drive) | Prefix::VerbatimDisk(drive) => Some(format!("{}:", drive as char)),
+        _ => None,
+    }
+}
+
+/// Returns the 1-based line and column of the given Location's (exclusive) end position.
+/// If the file can no longer be read, the Location is assumed to be on one line.
+fn end_line_and_column_of(location: &Location) -> (u32, u16) {
+    match source_for(location.filename) {
+        Ok(contents) => line_and_column_of(&contents, location.end.index),
+        Err(_) => (location.start.line, location.start.column.saturating_add(location.byte_length() as u16)),
+    }
+}
+
 impl<'a> Display for Location<'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
-        let filename = os_agnostic_display_path(self.filename);
+        let filename = os_agnostic_display_path(self.filename, COLORED_OUTPUT.load(SeqCst));
         write!(f, "{}:{}:{}", filename, self.start.line, self.start.column)
     }
 }
 
-impl<'a> Display for ErrorMessage<'a> {
+impl<'a> ErrorMessage<'a> {
+    /// The text of this message without any escape codes, even those of text colored before
+    /// it was put in the message. This is what machine-readable formats output.
+    fn plain_msg(&self) -> Cow<'_, str> {
+        strip_ansi(&self.msg)
+    }
+
+    /// Display this message on a single line as `path:line:column: severity: message`.
+    /// Any newlines within the message are replaced with spaces and labels and
+    /// suggestions are omitted so each message is exactly one line.
+    fn fmt_short(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let msg = self.plain_msg().replace('\n', " ");
+        write!(f, "{}: {}: {}", self.location, self.severity().name(), msg)
+    }
+
+    /// Display this message in the format used by GNU tools. This is similar to the short
+    /// format, except each label is also shown on its own line as a note at its location.
+    fn fmt_gnu(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        fmt_gnu_line(f, &self.location, self.severity().name(), &self.msg)?;
+        for (location, label) in &self.details.labels {
+            writeln!(f)?;
+            fmt_gnu_line(f, location, "note", label)?;
+        }
+        for note in &self.details.notes {
+            writeln!(f)?;
+            fmt_gnu_line(f, &self.location, "note", note)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the 1-based line and column of this message's (exclusive) end position.
+    fn end_line_and_column(&self) -> (u32, u16) {
+        end_line_and_column_of(&self.location)
+    }
+
+    /// Convert this message into a JSON object containing its file, start and end
+    /// positions, severity, and the message itself without any color codes.
+    /// Lines and columns are both 1-based and the end position is exclusive.
+    pub fn to_json(&self) -> serde_json::Value {
+        let (end_line, end_column) = self.end_line_and_column();
+
+        let mut json = serde_json::json!({
+            "file": &*os_agnostic_display_path(self.location.filename, false),
+            "severity": self.severity().name(),
+            "code": self.code.map(ErrorCode::code),
+            "start": { "line": self.location.start.line, "column": self.location.start.column },
+            "end": { "line": end_line, "column": end_column },
+            "message": self.plain_msg(),
+        });
+
+        if !self.details.labels.is_empty() {
+            json["labels"] = self
+                .details
+                .labels
+                .iter()
+                .map(|(location, label)| {
+                    let (end_line, end_column) = end_line_and_column_of(location);
+                    serde_json::json!({
+                        "file": &*os_agnostic_display_path(location.filename, false),
+                        "message": strip_ansi(label),
+                        "start": { "line": location.start.line, "column": location.start.column },
+                        "end": { "line": end_line, "column": end_column },
+                    })
+                })
+                .collect();
+        }
+
+        if !self.details.notes.is_empty() {
+            json["notes"] = self.details.notes.iter().map(|note| strip_ansi(note)).collect();
+        }
+
+        if let Some(suggestion) = &self.details.suggestion {
+            let (end_line, end_column) = end_line_and_column_of(&suggestion.location);
+            json["suggestion"] = serde_json::json!({
+                "message": suggestion.message,
+                "replacement": suggestion.replacement,
+                "start": { "line": suggestion.location.start.line, "column": suggestion.location.start.column },
+                "end": { "line": end_line, "column": end_column },
+            });
+        }
+        json
+    }
+
+    /// Convert this message into a `compiler-message` object as output by cargo. Labels are
+    /// secondary spans of the message while notes and the suggestion are its children. The
+    /// `rendered` field is the message as it would be displayed in the human-readable format.
+    fn to_cargo_json(&self) -> serde_json::Value {
+        let mut spans = vec![cargo_span(&self.location, true, None, None)];
+        spans
+            .extend(self.details.labels.iter().map(|(location, label)| cargo_span(location, false, Some(label), None)));
+
+        let mut children: Vec<_> = self.details.notes.iter().map(|note| cargo_child("note", note, vec![])).collect();
+        if let Some(suggestion) = &self.details.suggestion {
+            let span = cargo_span(&suggestion.location, true, None, Some(&suggestion.replacement));
+            children.push(cargo_child("help", &suggestion.message, vec![span]));
+        }
+
+        serde_json::json!({
+            "reason": "compiler-message",
+            "message": {
+                "message": self.plain_msg(),
+                "code": self.code.map(|code| serde_json::json!({
+                    "code": code.code(),
+                    "explanation": code.explanation(),
+                })),
+                "level": self.severity().name(),
+                "spans": spans,
+                "children": children,
+                "rendered": HumanMessage(self, COLORED_OUTPUT.load(SeqCst)).to_string(),
+            },
+        })
+    }
+
+    /// Convert this message into a SARIF `result` object. The rule id is the message's
+    /// ErrorCode if it has one, otherwise it is derived from the message's severity.
+    fn to_sarif_result(&self) -> serde_json::Value {
+        let (end_line, end_column) = self.end_line_and_column();
+
+        serde_json::json!({
+            "ruleId": match self.code {
+                Some(code) => code.code().to_owned(),
+                None => format!("ante::{}", self.severity().name()),
+            },
+            "level": self.severity().name(),
+            "message": { "text": self.plain_msg() },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": &*os_agnostic_display_path(self.location.filename, false) },
+                    "region": {
+                        "startLine": self.location.start.line,
+                        "startColumn": self.location.start.column,
+                        "endLine": end_line,
+                        "endColumn": end_column,
+                        "byteOffset": self.location.start.index,
+                        "byteLength": self.location.byte_length(),
+                    },
+                },
+            }],
+        })
+    }
+
+    /// Write this message for a human reader, with color only if `colored` is set. If `grouped_file`
+    /// is given, the message is being shown beneath a header for that file, so any location within
+    /// it is written as only its line and column.
+    fn fmt_human(&self, f: &mut Formatter, grouped_file: Option<&Path>, colored: bool) -> Result<(), std::fmt::Error> {
+        let msg = if colored { self.msg.to_string() } else { self.plain_msg().into_owned() };
+        let location = |location| GroupedLocation { location, grouped_file, colored };
+
+        writeln!(f, "{}\t{} {}", location(&self.location), self.marker(colored), msg)?;
+        fmt_source(f, &self.location, |text| self.color(text, colored), colored)?;
+
+        for (label_location, label) in &self.details.labels {
+            let label = if colored { Cow::Borrowed(label.as_str()) } else { strip_ansi(label) };
+            writeln!(f, "{}\t{}", location(label_location), label)?;
+            fmt_source(f, label_location, |text| label_color(text, colored), colored)?;
+        }
+
+        for note in &self.details.notes {
+            let note = if colored { Cow::Borrowed(note.as_str()) } else { strip_ansi(note) };
+            let marker = severity_color(ErrorType::Note, "note:", colored);
+            writeln!(f, "{}\t{} {}", location(&self.location), marker, note)?;
+        }
+
+        match &self.details.suggestion {
+            Some(suggestion) => fmt_suggestion(suggestion, f, grouped_file, colored),
+            None => Ok(()),
+        }
+    }
+
+    /// Display this message beneath a header naming the file it is in. Unlike displaying
+    /// the message directly, the location of the message and of any labels in the same file
+    /// omit the filename. As with displaying it directly, this does not count towards the error count.
+    pub fn in_file_group(&self) -> impl Display + '_ {
+        FileGroupedMessage(self)
+    }
+}
+
+/// A Location displayed as only its line and column if it is within `grouped_file`
+struct GroupedLocation<'l, 'a> {
+    location: &'l Location<'a>,
+    grouped_file: Option<&'l Path>,
+    colored: bool,
+}
+
+impl<'l, 'a> Display for GroupedLocation<'l, 'a> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
-        let start = self.location.start;
+        let location = self.location;
+        let text = if self.grouped_file == Some(location.filename) {
+            format!("{}:{}", location.start.line, location.start.column)
+        } else {
+            let filename = os_agnostic_display_path(location.filename, self.colored);
+            format!("{}:{}:{}", filename, location.start.line, location.start.column)
+        };
 
-        // An error isn't considered an error until it is actually printed out.
-        // That's why ERROR_COUNT is incremented here and not when ErrorMessage is constructed.
-        if self.error_type == ErrorType::Error {
-            ERROR_COUNT.fetch_add(1, SeqCst);
+        if HYPERLINKS.load(SeqCst) && self.colored {
+            let path = std::fs::canonicalize(location.filename).unwrap_or_else(|_| location.filename.to_owned());
+            let url = format!("file://{}:{}", path.display(), location.start.line);
+            write!(f, "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+        } else {
+            write!(f, "{}", text)
         }
+    }
+}
 
-        writeln!(f, "{}\t{} {}", self.location, self.marker(), self.msg)?;
+/// An ErrorMessage displayed in the human-readable format regardless of the current MessageFormat,
+/// colored only if the second field is set.
+struct HumanMessage<'m, 'a>(&'m ErrorMessage<'a>, bool);
 
-        let file_contents = read_file_or_panic(self.location.filename);
-        let line = file_contents.lines().nth(max(1, start.line) as usize - 1).unwrap_or("");
+impl<'m, 'a> Display for HumanMessage<'m, 'a> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        self.0.fmt_human(f, None, self.1)
+    }
+}
 
-        let start_column = max(1, start.column) as usize - 1;
-        let actual_len = min(self.location.length(), line.len() - start_column);
+/// Convert the given Location into a span of a cargo `compiler-message`, see `to_cargo_json`
+fn cargo_span(
+    location: &Location, is_primary: bool, label: Option<&str>, replacement: Option<&str>,
+) -> serde_json::Value {
+    let (end_line, end_column) = end_line_and_column_of(location);
+    serde_json::json!({
+        "file_name": &*os_agnostic_display_path(location.filename, false),
+        "byte_start": location.start.index,
+        "byte_end": location.end.index,
+        "line_start": location.start.line,
+        "line_end": end_line,
+        "column_start": location.start.column,
+        "column_end": end_column,
+        "is_primary": is_primary,
+        "text": [],
+        "label": label.map(strip_ansi),
+        "suggested_replacement": replacement,
+        "suggestion_applicability": replacement.map(|_| "MaybeIncorrect"),
+        "expansion": null,
+    })
+}
+
+/// A child message of a cargo `compiler-message` such as a note, see `to_cargo_json`
+fn cargo_child(level: &str, message: &str, spans: Vec<serde_json::Value>) -> serde_json::Value {
+    serde_json::json!({
+        "message": strip_ansi(message),
+        "code": null,
+        "level": level,
+        "spans": spans,
+        "children": [],
+        "rendered": null,
+    })
+}
+
+/// An ErrorMessage being displayed beneath a header for its file, see `ErrorMessage::in_file_group`
+struct FileGroupedMessage<'m, 'a>(&'m ErrorMessage<'a>);
+
+impl<'m, 'a> Display for FileGroupedMessage<'m, 'a> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let message = self.0;
+        match *MESSAGE_FORMAT.read().unwrap() {
+            MessageFormat::Human => message.fmt_human(f, Some(message.location.filename), COLORED_OUTPUT.load(SeqCst)),
+            // Only human-readable messages are grouped, the others are each self-contained
+            MessageFormat::Json | MessageFormat::Short | MessageFormat::Gnu | MessageFormat::CargoJson => {
+                write!(f, "{}", message)
+            },
+        }
+    }
+}
+
+/// Returns the header shown once before each group of messages in the given file
+pub fn file_group_header(path: &Path) -> String {
+    format!("{}:", os_agnostic_display_path(path, COLORED_OUTPUT.load(SeqCst)))
+}
+
+/// Write out the source lines the given Location points to with the Location highlighted
+fn fmt_source(
+    f: &mut Formatter, location: &Location, color: impl Fn(&str) -> ColoredString + Copy, colored: bool,
+) -> Result<(), std::fmt::Error> {
+    let start = location.start;
+
+    if location.is_dummy() {
+        return writeln!(f, "(generated code)");
+    }
+
+    // The file may have been moved or deleted since it was parsed. In that case we
+    // can still issue the message, we just can't point to the offending source.
+    let file_contents = match source_for(location.filename) {
+        Ok(contents) => contents,
+        Err(_) => return writeln!(f, "<source unavailable>"),
+    };
+
+    // Errors at the end of input may point one past the last character of the file
+    let start_index = min(start.index, file_contents.len());
+    let end_index = min(max(start_index, location.end.index), file_contents.len());
+
+    let start_line = max(1, start.line);
+    let end_index = start_index + trim_trailing_lines(&file_contents[start_index..end_index]).len();
+    let (end_line, end_column) = line_and_column_of(&file_contents, end_index);
+    let end_line = max(start_line, end_line);
+
+    // Lines before the start of the file or past its end are omitted from the context
+    let line_at = |line_number: u32| file_contents.lines().nth(line_number as usize - 1).unwrap_or("");
+    let context_lines = CONTEXT_LINES.load(SeqCst) as u32;
+    let line_count = file_contents.lines().count() as u32;
+    let first_line = max(1, start_line.saturating_sub(context_lines));
+    let last_line = max(end_line, min(end_line + context_lines, line_count));
+
+    for line_number in first_line..start_line {
+        write_context_line(f, LineNumber::new(line_number, last_line), line_at(line_number), colored)?;
+    }
+
+    if end_line == start_line {
+        let line = line_at(start_line);
+
+        // A position past the end of the line is shown just after its last character
+        let start_offset = byte_offset_of_column(line, start.column);
+        let actual_len = min(end_index - start_index, line.len() - start_offset);
 
         // In case we have an odd Location that has start.index = end.index,
         // we show a minimum of one indicator (^) to show where the error is.
-        // let adjusted_len = max(1, actual_len);
+        let end_offset = floor_char_boundary(line, start_offset + actual_len);
+        let line_number = LineNumber::new(start_line, last_line);
+        let highlight = Highlight { start: start_offset, end: end_offset, underline: true, color };
+        write_highlighted_line(f, line_number, "", line, highlight, colored)?;
+    } else {
+        // Multi-line spans are shown in full with a gutter to the left. Only the
+        // first and last lines are underlined since the lines in between are
+        // always highlighted in their entirety.
+        for line_number in start_line..=end_line {
+            let line = line_at(line_number);
+
+            let highlight_start = if line_number == start_line { byte_offset_of_column(line, start.column) } else { 0 };
+            let highlight_end =
+                if line_number == end_line { byte_offset_of_column(line, end_column) } else { line.len() };
+            let highlight_start = min(highlight_start, highlight_end);
+            let underline = line_number == start_line || line_number == end_line;
+            let highlight = Highlight { start: highlight_start, end: highlight_end, underline, color };
+            write_highlighted_line(f, LineNumber::new(line_number, last_line), "| ", line, highlight, colored)?;
+        }
+    }
 
-        // write the first part of the line, then the erroring part in red, then the rest
-        write!(f, "{}", &line[0..start_column])?;
-        write!(f, "{}", self.color(&line[start_column..start_column + actual_len]))?;
-        writeln!(f, "{}", &line[start_column + actual_len..])?;
+    for line_number in end_line + 1..=last_line {
+        write_context_line(f, LineNumber::new(line_number, last_line), line_at(line_number), colored)?;
+    }
+    Ok(())
+}
 
-        if !COLORED_OUTPUT.load(SeqCst) {
-            let padding = " ".repeat(start_column);
-            let indicator = self.color(&"^".repeat(max(1, actual_len)));
-            writeln!(f, "{}{}", padding, indicator)?;
+/// Returns the byte index of the end of the line containing `index`, not including its line
+/// ending. The `\r` of a `\r\n` line ending is excluded as well, as it is by `str::lines`,
+/// so that it isn't displayed and files with either line ending are shown the same.
+fn end_of_line(contents: &str, index: usize) -> usize {
+    let end = contents[index..].find('\n').map_or(contents.len(), |newline| index + newline);
+    if end > index && contents[..end].ends_with('\r') {
+        end - 1
+    } else {
+        end
+    }
+}
+
+/// Returns the byte offset into `line` of the given 1-based column. Columns count
+/// characters rather than bytes, so this always lands on a char boundary. Columns
+/// past the end of the line map to its length.
+fn byte_offset_of_column(line: &str, column: u16) -> usize {
+    let column = max(1, column) as usize - 1;
+    line.char_indices().nth(column).map_or(line.len(), |(offset, _)| offset)
+}
+
+/// Returns the largest char boundary in `line` which is at most `offset`
+fn floor_char_boundary(line: &str, offset: usize) -> usize {
+    (0..=min(offset, line.len())).rev().find(|offset| line.is_char_boundary(*offset)).unwrap_or(0)
+}
+
+/// Shorten the parts of a line before and after its highlighted part so that the line is at
+/// most `max_width` columns wide, replacing the text removed from each with `...`. The width
+/// left over after the highlighted part is split evenly between both sides, and any unused by
+/// one side is given to the other. The highlighted part itself is always kept in full so
+/// that the indicator beneath it still points to all of it.
+fn truncate_around(before: String, highlighted: &str, after: String, max_width: usize) -> (String, String) {
+    if before.width() + highlighted.width() + after.width() <= max_width {
+        return (before, after);
+    }
+
+    let remaining = max_width.saturating_sub(highlighted.width());
+    let before_width = min(before.width(), max(remaining / 2, remaining.saturating_sub(after.width())));
+    let after_width = remaining - before_width;
+    (truncate_start(before, before_width), truncate_end(after, after_width))
+}
+
+/// Keep only the end of `text` so that it is at most `max_width` columns wide, including
+/// the `...` replacing the start. The `...` is always shown if any text was removed.
+fn truncate_start(text: String, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text;
+    }
+
+    let mut width = "...".len();
+    let mut start = text.len();
+    for (i, c) in text.char_indices().rev() {
+        width += c.width().unwrap_or(0);
+        if width > max_width {
+            break;
         }
-        Ok(())
+        start = i;
+    }
+    format!("...{}", &text[start..])
+}
+
+/// Keep only the start of `text` so that it is at most `max_width` columns wide, including
+/// the `...` replacing the end. The `...` is always shown if any text was removed.
+fn truncate_end(text: String, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text;
+    }
+
+    let mut width = "...".len();
+    let mut end = 0;
+    for (i, c) in text.char_indices() {
+        width += c.width().unwrap_or(0);
+        if width > max_width {
+            break;
+        }
+        end = i + c.len_utf8();
+    }
+    format!("{}...", &text[..end])
+}
+
+/// Write a source line shown around the highlighted source to give it context
+fn write_context_line(
+    f: &mut Formatter, line_number: LineNumber, line: &str, colored: bool,
+) -> Result<(), std::fmt::Error> {
+    let mut line = expand_tabs(line, 0);
+    if let Some(max_width) = *MAX_LINE_WIDTH.read().unwrap() {
+        line = truncate_end(line, max_width);
+    }
+
+    let line = format!("{:>width$} | {}", line_number.line, line, width = line_number.width);
+    let line = line.trim_end();
+
+    if colored {
+        writeln!(f, "{}", line.dimmed())
+    } else {
+        writeln!(f, "{}", line)
+    }
+}
+
+/// Write the given suggestion's message followed by the line it applies to with
+/// the suggested replacement substituted in.
+fn fmt_suggestion(
+    suggestion: &Suggestion, f: &mut Formatter, grouped_file: Option<&Path>, colored: bool,
+) -> Result<(), std::fmt::Error> {
+    let location = GroupedLocation { location: &suggestion.location, grouped_file, colored };
+    let color = |text: &str| help_color(text, colored);
+    writeln!(f, "{}\t{} {}", location, color("help:"), suggestion.message)?;
+
+    let file_contents = match source_for(suggestion.location.filename) {
+        Ok(contents) => contents,
+        Err(_) => return writeln!(f, "<source unavailable>"),
+    };
+
+    // The location may be past the end of the file or within a character if the file changed
+    // since it was parsed, so it is clamped to the nearest preceding character boundary.
+    let line_number = LineNumber::new(suggestion.location.start.line, suggestion.location.start.line);
+    let start = floor_char_boundary(&file_contents, suggestion.location.start.index);
+    let end = floor_char_boundary(&file_contents, max(start, suggestion.location.end.index));
+    let line_start = file_contents[..start].rfind('\n').map_or(0, |newline| newline + 1);
+    let line_end = end_of_line(&file_contents, end);
+
+    let before = &file_contents[line_start..start];
+
+    // There is nothing to show in place of a deletion, so show the text being removed instead
+    if suggestion.replacement.is_empty() {
+        let line_end = end_of_line(&file_contents, start);
+        let line = &file_contents[line_start..line_end];
+        let removed_end = std::cmp::min(end, line_end) - line_start;
+        let highlight = Highlight { start: before.len(), end: removed_end, underline: true, color };
+        return write_highlighted_line(f, line_number, "", line, highlight, colored);
+    }
+
+    let line = format!("{}{}{}", before, suggestion.replacement, &file_contents[end..line_end]);
+    let replacement_end = before.len() + suggestion.replacement.len();
+    let highlight = Highlight { start: before.len(), end: replacement_end, underline: true, color };
+    write_highlighted_line(f, line_number, "", &line, highlight, colored)
+}
+
+/// Color the given string in the color used for secondary labels, if `colored` is set
+fn label_color(msg: &str, colored: bool) -> ColoredString {
+    if colored {
+        THEME.read().unwrap().label.paint(msg)
+    } else {
+        msg.normal()
+    }
+}
+
+/// Color the given string in the color used for suggestions, if `colored` is set
+fn help_color(msg: &str, colored: bool) -> ColoredString {
+    if colored {
+        THEME.read().unwrap().help.paint(msg)
+    } else {
+        msg.normal()
+    }
+}
+
+/// The number of a source line shown in the gutter to the left of it
+#[derive(Debug, Copy, Clone)]
+struct LineNumber {
+    line: u32,
+
+    /// The number of columns line numbers are padded to, so that the
+    /// gutter stays aligned across every line shown for a message.
+    width: usize,
+}
+
+impl LineNumber {
+    /// Create a LineNumber for the given line, padded to the width of the last line that will be shown
+    fn new(line: u32, last_line: u32) -> LineNumber {
+        LineNumber { line, width: last_line.to_string().len() }
+    }
+}
+
+/// The part of a source line to highlight and how to draw it, see `write_highlighted_line`
+struct Highlight<C> {
+    /// The byte offsets into the line where the highlighted part starts and (exclusively) ends
+    start: usize,
+    end: usize,
+
+    /// Whether an indicator line may be drawn beneath the highlighted part
+    underline: bool,
+
+    /// Colors the highlighted part and its indicator
+    color: C,
+}
+
+/// Write the given source line with the highlighted part in the highlight's color.
+/// If `colored` is false, an indicator line is written beneath instead, or as well if
+/// `always_underline` is set. Both are preceded by the line number gutter and then `gutter`.
+fn write_highlighted_line(
+    f: &mut Formatter, line_number: LineNumber, gutter: &str, line: &str,
+    highlight: Highlight<impl Fn(&str) -> ColoredString>, colored: bool,
+) -> Result<(), std::fmt::Error> {
+    let Highlight { start, end, underline, color } = highlight;
+    let width = line_number.width;
+    // Tabs are expanded so that their width is known when lining up the indicator.
+    // Each part continues from the width of the last since tab stops depend on the column.
+    let before = expand_tabs(&line[0..start], 0);
+    let highlighted = expand_tabs(&line[start..end], before.width());
+    let after = expand_tabs(&line[end..], before.width() + highlighted.width());
+
+    let (before, after) = match *MAX_LINE_WIDTH.read().unwrap() {
+        Some(max_width) => truncate_around(before, &highlighted, after, max_width),
+        None => (before, after),
+    };
+
+    // write the first part of the line, then the erroring part in red, then the rest
+    write!(f, "{:>width$} | {}{}", line_number.line, gutter, before)?;
+    write!(f, "{}", color(&highlighted))?;
+    writeln!(f, "{}", after)?;
+
+    // Pad and underline by display width rather than by length so that the
+    // indicator still lines up when the line contains wide or zero-width characters.
+    if underline && (!colored || ALWAYS_UNDERLINE.load(SeqCst)) {
+        let padding = " ".repeat(before.width());
+        let indicator = CARET_STYLE.read().unwrap().indicator(max(1, highlighted.width()));
+        let indicator = color(&indicator);
+        writeln!(f, "{:width$} | {}{}{}", "", gutter, padding, indicator)?;
+    }
+    Ok(())
+}
+
+/// Serialize a batch of messages as a SARIF 2.1.0 log containing a single run.
+/// Unlike emitting a message, this does not count towards the error count.
+pub fn write_sarif(messages: &[ErrorMessage], out: &mut impl Write) -> std::io::Result<()> {
+    let results: Vec<_> = messages.iter().map(ErrorMessage::to_sarif_result).collect();
+
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "ante",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            },
+            "results": results,
+        }],
+    });
+
+    writeln!(out, "{}", log)
+}
+
+/// Render a batch of messages in the human-readable format without color to compare against
+/// a snapshot in a test. Messages are sorted first so the result is the same regardless
+/// of the order they were collected in. Unlike emitting a message, this does not count towards
+/// the error count, and it does not depend on whether colored output is currently enabled.
+#[cfg(test)]
+pub fn render_to_string(messages: &[ErrorMessage]) -> String {
+    let mut messages: Vec<_> = messages.iter().collect();
+    messages.sort();
+    messages.iter().map(|message| format!("{}\n", HumanMessage(message, false))).collect()
+}
+
+impl<'a> Display for ErrorMessage<'a> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match *MESSAGE_FORMAT.read().unwrap() {
+            MessageFormat::Human => self.fmt_human(f, None, COLORED_OUTPUT.load(SeqCst)),
+            MessageFormat::Json => write!(f, "{}", self.to_json()),
+            MessageFormat::Short => self.fmt_short(f),
+            MessageFormat::Gnu => self.fmt_gnu(f),
+            MessageFormat::CargoJson => write!(f, "{}", self.to_cargo_json()),
+        }
+    }
+}
+
+/// Lets ErrorMessages be used wherever a standard error is expected, e.g. as a `Box<dyn Error>`.
+/// Displaying the boxed error does not count towards the number of messages issued, see `ErrorMessage::emit`.
+impl<'a> std::error::Error for ErrorMessage<'a> {}
+
+/// Remove any ANSI escape codes from the given text, e.g. those coloring it. Both control
+/// sequences such as `\x1b[31m` and operating system commands such as OSC 8 hyperlinks
+/// are removed.
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            // A control sequence ends with its first character within '@'..='~'
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            },
+            // An operating system command ends with either BEL or `ESC \`
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            },
+            // Any other escape is a single character
+            _ => (),
+        }
+    }
+
+    Cow::Owned(result)
+}
+
+/// Write a single `path:line:column: severity: message` line of a message in the GNU format.
+/// Lines and columns there are expected to start at 1, so builtin locations at line 0,
+/// column 0 are shown at line 1, column 1 instead.
+fn fmt_gnu_line(f: &mut Formatter, location: &Location, severity: &str, msg: &str) -> Result<(), std::fmt::Error> {
+    let path = os_agnostic_display_path(location.filename, COLORED_OUTPUT.load(SeqCst));
+    let line = max(location.start.line, 1);
+    let column = max(location.start.column, 1);
+    write!(f, "{}:{}:{}: {}: {}", path, line, column, severity, strip_ansi(msg).replace('\n', " "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::location::{EndPosition, Position};
+
+    fn location(path: &'static Path, index: usize, line: u32, column: u16, end: usize) -> Location<'static> {
+        Location::new(path, Position { index, line, column }, EndPosition::new(end))
+    }
+
+    #[test]
+    fn render_to_string_sorts_messages_without_color() {
+        let path = Path::new("render_to_string.an");
+        cache_source(path, "a = 1\nb = 2\n");
+
+        let messages = [
+            ErrorMessage::warning("second", location(path, 6, 2, 1, 7)),
+            ErrorMessage::error("first".red(), location(path, 0, 1, 1, 1)),
+        ];
+
+        let expected = "render_to_string.an:1:1\terror: first\n1 | a = 1\n  | ^\n2 | b = 2\n\n\
+                        render_to_string.an:2:1\twarning: second\n1 | a = 1\n2 | b = 2\n  | ^\n\n";
+        assert_eq!(render_to_string(&messages), expected);
+    }
+
+    #[test]
+    fn write_sarif_reports_rule_level_and_region() {
+        let path = Path::new("write_sarif.an");
+        cache_source(path, "a = 1\nb = 2\n");
+
+        let messages = [
+            ErrorMessage::error("cannot assign".red(), location(path, 6, 2, 1, 7))
+                .with_code(ErrorCode::AssignToImmutable),
+            ErrorMessage::warning("unused", location(path, 0, 1, 1, 1)),
+        ];
+
+        let before = get_message_counts();
+        let mut out = vec![];
+        write_sarif(&messages, &mut out).unwrap();
+        assert_eq!(get_message_counts() - before, MessageCounts::default());
+
+        let log: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+
+        let results = &log["runs"][0]["results"];
+        assert_eq!(results[0]["ruleId"], "E0018");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[0]["message"]["text"], "cannot assign");
+        assert_eq!(results[1]["ruleId"], "ante::warning");
+        assert_eq!(results[1]["level"], "warning");
+
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "write_sarif.an");
+        assert_eq!(
+            location["region"],
+            serde_json::json!({
+                "startLine": 2, "startColumn": 1, "endLine": 2, "endColumn": 2, "byteOffset": 6, "byteLength": 1,
+            })
+        );
+    }
+
+    #[test]
+    fn evicted_sources_are_read_from_disk_again() {
+        let first = Path::new("evict_source_first.an");
+        let second = Path::new("evict_source_second.an");
+        cache_source(first, "a = 1\n");
+        cache_source(second, "b = 2\n");
+
+        evict_source(first);
+        assert!(source_for(first).is_err());
+        assert_eq!(&*source_for(second).unwrap(), "b = 2\n");
+
+        clear_source_cache();
+        assert!(source_for(second).is_err());
+    }
+
+    #[test]
+    fn is_source_current_compares_the_cache_against_the_file() {
+        let path = std::env::temp_dir().join(format!("ante_is_source_current_{}.an", std::process::id()));
+        std::fs::write(&path, "a = 1\n").unwrap();
+        assert_eq!(is_source_current(&path), None);
+
+        source_for(&path).unwrap();
+        assert_eq!(is_source_current(&path), Some(true));
+
+        std::fs::write(&path, "a = 2\n").unwrap();
+        assert_eq!(is_source_current(&path), Some(false));
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(is_source_current(&path), None);
+
+        let virtual_file = Path::new("is_source_current_virtual.an");
+        cache_source(virtual_file, "a = 1\n");
+        assert_eq!(is_source_current(virtual_file), None);
+    }
+
+    #[test]
+    fn boxed_errors_display_without_being_counted() {
+        let path = Path::new("boxed_error.an");
+        cache_source(path, "a = 1\n");
+
+        let before = get_message_counts();
+        let error: Box<dyn std::error::Error> = Box::new(ErrorMessage::error("boxed", location(path, 0, 1, 1, 1)));
+        let displayed = strip_ansi(&error.to_string()).into_owned();
+
+        assert!(displayed.starts_with("boxed_error.an:1:1\terror: boxed\n"), "{}", displayed);
+        assert!(error.source().is_none());
+        assert_eq!(get_message_counts() - before, MessageCounts::default());
+    }
+
+    #[test]
+    fn accessors_return_the_code_and_primary_location() {
+        let path = Path::new("accessors.an");
+        let primary = location(path, 6, 2, 1, 7);
+        let label = location(path, 0, 1, 1, 1);
+
+        let message = ErrorMessage::error("error", primary).with_label(label, "label");
+        assert_eq!(message.code(), None);
+        assert_eq!(message.location(), primary);
+
+        let message = message.with_code(ErrorCode::AssignToImmutable);
+        assert_eq!(message.code(), Some(ErrorCode::AssignToImmutable));
+        assert_eq!(message.location(), primary);
+    }
+
+    #[test]
+    fn suggestions_outside_the_source_are_clamped() {
+        let path = Path::new("stale_suggestion.an");
+        cache_source(path, "a = \u{e9}\n");
+        let error = || ErrorMessage::error("error", location(path, 0, 1, 1, 1));
+
+        // As if the file was shortened after it was parsed
+        let past_the_end = error().with_suggestion("add one", location(path, 20, 3, 1, 25), "1");
+        let rendered = render_to_string(&[past_the_end]);
+        assert!(rendered.ends_with("stale_suggestion.an:3:1\thelp: add one\n3 | 1\n  | ^\n\n"), "{}", rendered);
+
+        // Starts in the middle of the two bytes of `\u{e9}`
+        let within_a_char = error().with_suggestion("replace it", location(path, 5, 1, 6, 6), "e");
+        let rendered = render_to_string(&[within_a_char]);
+        assert!(rendered.ends_with("1:6\thelp: replace it\n1 | a = e\n  |     ^\n\n"), "{}", rendered);
+    }
+
+    #[test]
+    fn strip_ansi_borrows_plain_text() {
+        assert!(matches!(strip_ansi("plain text"), Cow::Borrowed("plain text")));
+        assert_eq!(strip_ansi(""), "");
+    }
+
+    #[test]
+    fn strip_ansi_removes_control_sequences() {
+        assert_eq!(strip_ansi("\x1b[1;31merror\x1b[0m: oops"), "error: oops");
+        assert_eq!(strip_ansi("\x1b[38;2;200;100;50mrgb\x1b[0m"), "rgb");
+        assert_eq!(strip_ansi("a\x1b[mb"), "ab");
+    }
+
+    #[test]
+    fn strip_ansi_removes_hyperlinks() {
+        let link = "\x1b]8;;file:///a.an\x1b\\a.an\x1b]8;;\x1b\\:1:1";
+        assert_eq!(strip_ansi(link), "a.an:1:1");
+
+        let terminated_by_bel = "\x1b]8;;file:///a.an\x07a.an\x1b]8;;\x07";
+        assert_eq!(strip_ansi(terminated_by_bel), "a.an");
+    }
+
+    #[test]
+    fn color_follows_the_terminal_when_nothing_is_set() {
+        assert!(color_by_default(None, None, true));
+        assert!(!color_by_default(None, None, false));
+        assert!(color_by_default(Some("auto"), None, true));
+        assert!(!color_by_default(Some("auto"), None, false));
+    }
+
+    #[test]
+    fn no_color_disables_coloring_with_any_value() {
+        for value in ["1", "", "false"] {
+            let no_color = Some(OsStr::new(value));
+            assert!(!color_by_default(None, no_color, true));
+            assert!(!color_by_default(Some("auto"), no_color, true));
+        }
+    }
+
+    #[test]
+    fn ante_color_overrides_no_color_and_the_terminal() {
+        let no_color = Some(OsStr::new("1"));
+        for stderr_is_terminal in [true, false] {
+            assert!(color_by_default(Some("always"), None, stderr_is_terminal));
+            assert!(color_by_default(Some("always"), no_color, stderr_is_terminal));
+            assert!(!color_by_default(Some("never"), None, stderr_is_terminal));
+            assert!(!color_by_default(Some("never"), no_color, stderr_is_terminal));
+        }
+    }
+
+    #[test]
+    fn unrecognized_ante_color_values_are_treated_as_auto() {
+        assert!(color_by_default(Some("yes"), None, true));
+        assert!(!color_by_default(Some("yes"), Some(OsStr::new("1")), true));
+    }
+
+    #[test]
+    fn messages_in_unreadable_files_show_a_placeholder() {
+        let path = Path::new("this/file/does/not/exist.an");
+        let rendered = render_to_string(&[ErrorMessage::error("missing", location(path, 4, 1, 5, 6))]);
+        assert_eq!(rendered, "this/file/does/not/exist.an:1:5\terror: missing\n<source unavailable>\n\n");
+    }
+
+    #[test]
+    fn sources_are_read_from_disk_only_once() {
+        let path = std::env::temp_dir().join(format!("ante_read_once_{}.an", std::process::id()));
+        std::fs::write(&path, "a = 1\n").unwrap();
+
+        let message = || ErrorMessage::error("read once", Location::new(&path, Position::begin(), EndPosition::new(1)));
+        assert!(render_to_string(&[message()]).contains("1 | a = 1\n"));
+
+        // Neither changing nor removing the file affects messages once it has been read
+        std::fs::write(&path, "b = 2\n").unwrap();
+        assert!(render_to_string(&[message()]).contains("1 | a = 1\n"));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(render_to_string(&[message()]).contains("1 | a = 1\n"));
+    }
+
+    #[test]
+    fn always_underline_draws_indicators_in_colored_output() {
+        let path = Path::new("always_underline.an");
+        cache_source(path, "a = 1\n");
+
+        // This is global but only affects the indicator lines of colored output, which no other test checks
+        always_underline(true);
+        let message = ErrorMessage::error("underlined", location(path, 4, 1, 5, 5));
+        let rendered = strip_ansi(&HumanMessage(&message, true).to_string()).into_owned();
+        assert!(rendered.ends_with("1 | a = 1\n  |     ^\n"), "{}", rendered);
+    }
+
+    #[test]
+    fn messages_in_virtual_files_show_the_registered_source() {
+        let path = Path::new("<stdin>");
+        assert!(!path.exists());
+        cache_source(path, "a = 1\nb = c\n");
+
+        let rendered = render_to_string(&[ErrorMessage::error("`c` is undefined", location(path, 10, 2, 5, 11))]);
+        assert_eq!(rendered, "<stdin>:2:5\terror: `c` is undefined\n1 | a = 1\n2 | b = c\n  |     ^\n\n");
+    }
+
+    #[test]
+    fn hyperlinks_wrap_locations_in_colored_output() {
+        let path = Path::new("hyperlinks.an");
+        cache_source(path, "a = 1\n");
+        let message = ErrorMessage::error("linked", location(path, 0, 1, 1, 1));
+        let url = format!("file://{}:1", path.display());
+
+        // This is global but only affects the locations in colored output, which no other test checks
+        hyperlinks(true);
+        let linked = HumanMessage(&message, true).to_string();
+        assert!(linked.starts_with(&format!("\x1b]8;;{}\x1b\\", url)), "{:?}", linked);
+        assert!(linked.contains("hyperlinks.an:1:1\x1b]8;;\x1b\\"), "{:?}", linked);
+        assert!(!HumanMessage(&message, false).to_string().contains("\x1b]8;;"));
+
+        hyperlinks(false);
+        assert!(!HumanMessage(&message, true).to_string().contains("\x1b]8;;"));
+    }
+
+    #[test]
+    fn paths_under_the_root_are_shown_relative_to_it() {
+        // This is global but only affects paths under a root no other test uses
+        path_root(Some(PathBuf::from("/ante_path_root/project")));
+        let display = |path: &str| os_agnostic_display_path(Path::new(path), false).to_string();
+
+        assert_eq!(display("/ante_path_root/project/src/main.an"), "src/main.an");
+        assert_eq!(display("/ante_path_root/project"), "/ante_path_root/project");
+        assert_eq!(display("/ante_path_root/other/main.an"), "/ante_path_root/other/main.an");
+        assert_eq!(display("relative/main.an"), "relative/main.an");
+        path_root(None);
+    }
+
+    #[test]
+    fn drives_of_windows_prefixes_are_shown() {
+        assert_eq!(display_drive(Prefix::Disk(b'C')).as_deref(), Some("C:"));
+        assert_eq!(display_drive(Prefix::VerbatimDisk(b'd')).as_deref(), Some("d:"));
+        assert_eq!(display_drive(Prefix::UNC(OsStr::new("server"), OsStr::new("share"))), None);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_paths_are_shown_with_their_drive() {
+        let display = |path: &str| os_agnostic_display_path(Path::new(path), false).to_string();
+        assert_eq!(display(r"C:\a\b.an"), "C:/a/b.an");
+        assert_eq!(display(r"\\?\C:\a\b.an"), "C:/a/b.an");
+    }
+
+    #[test]
+    fn messages_in_generated_code_are_shown_without_source() {
+        let dummy = Location::dummy();
+        assert!(dummy.is_dummy());
+        assert!(!dummy.filename.exists());
+
+        let rendered = render_to_string(&[ErrorMessage::error("generated", dummy)]);
+        assert_eq!(rendered, "<generated>:0:0\terror: generated\n(generated code)\n\n");
+        assert!(source_for(dummy.filename).is_err(), "the dummy file should never be read or cached");
     }
 }
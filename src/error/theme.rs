@@ -0,0 +1,328 @@
+//! theme.rs - Defines the Theme used to color each part of a message when
+//! colored output is enabled. The default theme is `Theme::colored()`, other
+//! themes can be made by overriding some of its styles, e.g.
+//! `Theme::colored().with_error(Style::new().color(Color::Magenta).bold())`,
+//! and set with `error::set_theme`. See `Theme::builtin` for the themes
+//! provided by default.
+//!
+//! Themes may also be loaded from a theme file with a line for each style
+//! giving its name and a description of it, e.g. `error = "bold bright red"`.
+//! Styles left out of the file keep their color from the default theme.
+use colored::{Color, ColoredString, Colorize};
+use std::str::FromStr;
+
+/// The color and emphasis to display some text with
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    /// A style which displays text unchanged
+    pub const fn new() -> Style {
+        Style { color: None, bold: false, italic: false, underline: false }
+    }
+
+    pub const fn color(self, color: Color) -> Style {
+        Style { color: Some(color), ..self }
+    }
+
+    /// Use a 24-bit color. These are only shown correctly by terminals supporting truecolor.
+    pub const fn rgb(self, r: u8, g: u8, b: u8) -> Style {
+        self.color(Color::TrueColor { r, g, b })
+    }
+
+    pub const fn bold(self) -> Style {
+        Style { bold: true, ..self }
+    }
+
+    pub const fn italic(self) -> Style {
+        Style { italic: true, ..self }
+    }
+
+    pub const fn underline(self) -> Style {
+        Style { underline: true, ..self }
+    }
+
+    /// Returns the given text displayed in this style
+    pub fn paint(self, text: &str) -> ColoredString {
+        let mut result = match self.color {
+            Some(color) => text.color(color),
+            None => text.normal(),
+        };
+        if self.bold {
+            result = result.bold();
+        }
+        if self.italic {
+            result = result.italic();
+        }
+        if self.underline {
+            result = result.underline();
+        }
+        result
+    }
+}
+
+/// The name of each style in a theme file
+const STYLE_NAMES: [&str; 6] = ["error", "warning", "note", "label", "help", "path"];
+
+/// The styles of each part of a message
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// The `error:` marker and the source it points to
+    pub error: Style,
+
+    /// The `warning:` marker and the source it points to
+    pub warning: Style,
+
+    /// The `note:` marker and the source it points to
+    pub note: Style,
+
+    /// Secondary labels and the source they point to
+    pub label: Style,
+
+    /// The `help:` marker of a suggestion and its replacement text
+    pub help: Style,
+
+    /// The file path at the start of each message
+    pub path: Style,
+}
+
+impl Theme {
+    /// The default theme
+    pub const fn colored() -> Theme {
+        Theme {
+            error: Style::new().color(Color::Red),
+            warning: Style::new().color(Color::Yellow),
+            note: Style::new().color(Color::Magenta),
+            label: Style::new().color(Color::Blue),
+            help: Style::new().color(Color::Cyan),
+            path: Style::new().italic(),
+        }
+    }
+
+    /// A theme for readers who find the default colors hard to tell apart. Each part
+    /// is bold, bright, and underlined so it stands out by more than its hue alone.
+    pub const fn high_contrast() -> Theme {
+        Theme {
+            error: Style::new().color(Color::BrightRed).bold().underline(),
+            warning: Style::new().color(Color::BrightYellow).bold().underline(),
+            note: Style::new().color(Color::BrightMagenta).bold().underline(),
+            label: Style::new().color(Color::BrightWhite).bold().underline(),
+            help: Style::new().color(Color::BrightGreen).bold().underline(),
+            path: Style::new().bold(),
+        }
+    }
+
+    /// Returns the builtin theme with the given name, either "default" or "high-contrast"
+    pub fn builtin(name: &str) -> Option<Theme> {
+        match name {
+            "default" => Some(Theme::colored()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            _ => None,
+        }
+    }
+
+    pub const fn with_error(self, error: Style) -> Theme {
+        Theme { error, ..self }
+    }
+
+    pub const fn with_warning(self, warning: Style) -> Theme {
+        Theme { warning, ..self }
+    }
+
+    pub const fn with_note(self, note: Style) -> Theme {
+        Theme { note, ..self }
+    }
+
+    pub const fn with_label(self, label: Style) -> Theme {
+        Theme { label, ..self }
+    }
+
+    pub const fn with_help(self, help: Style) -> Theme {
+        Theme { help, ..self }
+    }
+
+    pub const fn with_path(self, path: Style) -> Theme {
+        Theme { path, ..self }
+    }
+
+    /// Parse a theme from the contents of a theme file. Each line of the file is either blank,
+    /// a comment starting with `#`, or `name = "style"` where the style is a double-quoted
+    /// description as accepted by `Style::from_str`. This resembles TOML but is much narrower:
+    /// comments may not follow a style on the same line, and single-quoted strings and escapes
+    /// are not supported. On failure, the returned message says which line is invalid and why.
+    pub fn from_theme_file(source: &str) -> Result<Theme, String> {
+        let mut theme = Theme::colored();
+
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: String| format!("line {}: {}", i + 1, message);
+
+            let (name, value) =
+                line.split_once('=').ok_or_else(|| error(format!("expected `name = \"style\"`, found `{}`", line)))?;
+
+            let name = name.trim();
+            let style = theme.style_mut(name).ok_or_else(|| {
+                let expected = STYLE_NAMES.join(", ");
+                error(format!("unknown style `{}`, expected one of: {}", name, expected))
+            })?;
+
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .ok_or_else(|| error(format!("the style for `{}` should be a quoted string", name)))?;
+
+            *style = value.parse().map_err(error)?;
+        }
+
+        Ok(theme)
+    }
+
+    fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        match name {
+            "error" => Some(&mut self.error),
+            "warning" => Some(&mut self.warning),
+            "note" => Some(&mut self.note),
+            "label" => Some(&mut self.label),
+            "help" => Some(&mut self.help),
+            "path" => Some(&mut self.path),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a style from a space-separated list of attributes and at most one color,
+/// e.g. "bold bright red". Valid colors are those accepted by `colored::Color`,
+/// along with 24-bit colors written as `rgb(r, g, b)`.
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(description: &str) -> Result<Style, String> {
+        let mut style = Style::new();
+        let mut words = description.split_whitespace();
+
+        while let Some(word) = words.next() {
+            match word {
+                "bold" => style = style.bold(),
+                "italic" => style = style.italic(),
+                "underline" => style = style.underline(),
+                _ => {
+                    // Bright colors are made of two words, e.g. "bright red",
+                    // and rgb colors may contain spaces between their components.
+                    let mut color = word.to_owned();
+                    if word == "bright" {
+                        color = format!("{} {}", word, words.next().unwrap_or_default());
+                    } else if word.starts_with("rgb(") {
+                        while !color.ends_with(')') {
+                            match words.next() {
+                                Some(word) => color += word,
+                                None => break,
+                            }
+                        }
+                    }
+
+                    if style.color.is_some() {
+                        return Err(format!("`{}` has more than one color", description));
+                    }
+
+                    let color = parse_color(&color).ok_or_else(|| format!("unknown color `{}`", color))?;
+                    style = style.color(color);
+                },
+            }
+        }
+
+        Ok(style)
+    }
+}
+
+/// Parse either a named color or an rgb color like `rgb(200,100,50)`
+fn parse_color(color: &str) -> Option<Color> {
+    match color.strip_prefix("rgb(").and_then(|color| color.strip_suffix(')')) {
+        Some(components) => {
+            let components = components.split(',').map(|component| component.trim().parse::<u8>().ok());
+            match components.collect::<Option<Vec<_>>>()?[..] {
+                [r, g, b] => Some(Color::TrueColor { r, g, b }),
+                _ => None,
+            }
+        },
+        None => Color::from_str(color).ok(),
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::colored()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_overrides_a_single_style() {
+        let error = Style::new().color(Color::Green).bold();
+        let theme = Theme::colored().with_error(error).with_path(Style::new());
+
+        assert_eq!(theme.error, error);
+        assert_eq!(theme.path, Style::new());
+        assert_eq!(Theme { error: Theme::colored().error, path: Theme::colored().path, ..theme }, Theme::colored());
+    }
+
+    #[test]
+    fn from_theme_file_overrides_the_styles_it_names() {
+        let source = "# A comment\n\nerror = \"bold bright red\"\n  note=\"underline\"  \n";
+        let theme = Theme::from_theme_file(source).unwrap();
+
+        assert_eq!(theme.error, Style::new().color(Color::BrightRed).bold());
+        assert_eq!(theme.note, Style::new().underline());
+        assert_eq!(theme.warning, Theme::colored().warning);
+    }
+
+    #[test]
+    fn from_theme_file_rejects_unknown_styles() {
+        let error = Theme::from_theme_file("error = \"red\"\nerrors = \"red\"\n").unwrap_err();
+        assert_eq!(error, "line 2: unknown style `errors`, expected one of: error, warning, note, label, help, path");
+
+        let error = Theme::from_theme_file("error = red").unwrap_err();
+        assert_eq!(error, "line 1: the style for `error` should be a quoted string");
+
+        let error = Theme::from_theme_file("error = \"bold blurple\"").unwrap_err();
+        assert_eq!(error, "line 1: unknown color `blurple`");
+    }
+
+    #[test]
+    fn rgb_colors_use_truecolor_escapes() {
+        let color = parse_color("rgb(200,100,50)").unwrap();
+        assert_eq!(color, Color::TrueColor { r: 200, g: 100, b: 50 });
+        assert_eq!(color.to_fg_str(), "38;2;200;100;50");
+
+        assert_eq!("bold rgb(200, 100, 50)".parse(), Ok(Style::new().rgb(200, 100, 50).bold()));
+        assert_eq!(parse_color("rgb(200,100)"), None);
+        assert_eq!(parse_color("rgb(256,100,50)"), None);
+    }
+
+    #[test]
+    fn high_contrast_styles_stand_out_by_more_than_hue() {
+        let theme = Theme::high_contrast();
+        let styles = [theme.error, theme.warning, theme.note, theme.label, theme.help, theme.path];
+        assert!(styles.iter().all(|style| style.bold || style.underline));
+        assert!(styles[..5].iter().all(|style| style.underline));
+    }
+
+    #[test]
+    fn builtin_themes_are_found_by_name() {
+        assert_eq!(Theme::builtin("default"), Some(Theme::colored()));
+        assert_eq!(Theme::builtin("high-contrast"), Some(Theme::high_contrast()));
+        assert_eq!(Theme::builtin("solarized"), None);
+    }
+}
@@ -136,7 +136,7 @@ impl<'c> Context<'c> {
             VariantTag::False => 0,
             VariantTag::Unit => 0,
             VariantTag::Literal(literal) => match literal {
-                ast::LiteralKind::Integer(x, _) => (*x).try_into().unwrap(), // TODO: larger tags
+                ast::LiteralKind::Integer(x, ..) => (*x).try_into().unwrap(), // TODO: larger tags
                 ast::LiteralKind::Float(_, _) => todo!(),
                 ast::LiteralKind::String(_) => todo!(),
                 ast::LiteralKind::Char(x) => {
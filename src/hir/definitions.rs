@@ -64,6 +64,7 @@ impl std::hash::Hash for DefinitionType {
                 types::Type::Function(_) => (),
                 types::Type::TypeApplication(_, _) => (),
                 types::Type::Ref(_) => (),
+                types::Type::Error => (),
                 types::Type::Struct(field_names, _) => {
                     for name in field_names {
                         name.hash(state);
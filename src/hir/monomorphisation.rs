@@ -221,6 +221,7 @@ impl<'c> Context<'c> {
                 },
             },
             Effects(effects) => self.follow_all_effect_bindings_inner(effects, fuel),
+            Error => typ.clone(),
         }
     }
 
@@ -360,6 +361,7 @@ impl<'c> Context<'c> {
                 }
             },
             Effects(_) => unreachable!(),
+            Error => unreachable!("Type errors should prevent monomorphisation"),
         }
     }
 
@@ -571,6 +573,7 @@ impl<'c> Context<'c> {
                 Type::Tuple(fmap(fields, |(_, field)| self.convert_type_inner(field, fuel)))
             },
             Effects(_) => unreachable!(),
+            Error => unreachable!("Type errors should prevent monomorphisation"),
         }
     }
 
@@ -595,7 +598,7 @@ impl<'c> Context<'c> {
         use hir::Literal::*;
 
         match &literal.kind {
-            ast::LiteralKind::Integer(n, _) => {
+            ast::LiteralKind::Integer(n, ..) => {
                 let kind = match self.convert_type(typ) {
                     Type::Primitive(hir::PrimitiveType::Integer(kind)) => kind,
                     other => unreachable!("monomorphise_literal: expected integer type, found {}", other),
@@ -698,7 +701,7 @@ impl<'c> Context<'c> {
                 &mut self.cache,
                 "Unification error during monomorphisation: Could not unify definition $2 with instantiation $1",
             )
-            .map_err(|error| eprintln!("{}", error))
+            .map_err(|error| error.issue())
             .expect("Unification error during monomorphisation");
 
             self.monomorphisation_bindings.push(Rc::new(bindings.bindings));
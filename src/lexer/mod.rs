@@ -311,7 +311,7 @@ impl<'cache, 'contents> Lexer<'cache, 'contents> {
             let integer = integer_string.parse().unwrap();
             let location = self.locate();
             match self.lex_integer_suffix() {
-                Ok(suffix) => Some((Token::IntegerLiteral(integer, suffix), location)),
+                Ok(suffix) => Some((Token::IntegerLiteral(integer, suffix, false), location)),
                 Err(lexer_error) => Some((lexer_error, location)),
             }
         }
@@ -323,9 +323,9 @@ impl<'cache, 'contents> Lexer<'cache, 'contents> {
         if self.current.is_numeric() {
             self.lex_number().map(|(token, location)| {
                 let token = match token {
-                    Token::IntegerLiteral(x, kind) => {
+                    Token::IntegerLiteral(x, kind, _) => {
                         let x = format!("-{}", x).parse::<i64>().unwrap();
-                        Token::IntegerLiteral(x as u64, kind)
+                        Token::IntegerLiteral(x as u64, kind, true)
                     },
                     Token::FloatLiteral(x, kind) => Token::FloatLiteral(-x, kind),
                     _ => unreachable!(),
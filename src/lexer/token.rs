@@ -7,6 +7,7 @@
 //! the lexing phase of the compiler. The resulting tokens are then
 //! fed into the parser to verify the program's grammar and create
 //! an abstract syntax tree.
+use std::convert::TryFrom;
 use std::fmt::{self, Display};
 
 /// Lexing can fail with these errors, though the Lexer just
@@ -47,6 +48,27 @@ pub enum IntegerKind {
     Usz,
 }
 
+impl IntegerKind {
+    /// True if an integer literal with the given value fits in an integer of this kind.
+    /// Negative literals are stored in two's complement so their value is reinterpreted
+    /// as an i64 first, while positive literals above i64::MAX fit in no signed kind.
+    /// Isz and Usz are assumed to be 64 bits wide.
+    pub fn can_hold_literal(self, value: u64, negative: bool) -> bool {
+        use IntegerKind::*;
+        let value = if negative { i128::from(value as i64) } else { i128::from(value) };
+        match self {
+            I8 => i8::try_from(value).is_ok(),
+            I16 => i16::try_from(value).is_ok(),
+            I32 => i32::try_from(value).is_ok(),
+            I64 | Isz => i64::try_from(value).is_ok(),
+            U8 => u8::try_from(value).is_ok(),
+            U16 => u16::try_from(value).is_ok(),
+            U32 => u32::try_from(value).is_ok(),
+            U64 | Usz => u64::try_from(value).is_ok(),
+        }
+    }
+}
+
 /// Each float literal is polymorphic over the `Float a` type. The `a` is the
 /// specific FloatKind of the float which is later resolved to one of these
 /// variants (or kept generic if the code allows).
@@ -68,7 +90,9 @@ pub enum Token {
 
     Identifier(String),
     StringLiteral(String),
-    IntegerLiteral(u64, Option<IntegerKind>),
+    /// An integer literal and whether it is negative. The values of negative
+    /// literals are stored in two's complement.
+    IntegerLiteral(u64, Option<IntegerKind>, bool),
     FloatLiteral(f64, Option<FloatKind>),
     CharLiteral(char),
     BooleanLiteral(bool),
@@ -254,7 +278,7 @@ impl Display for Token {
 
             Token::Identifier(_) => write!(f, "an identifier"),
             Token::StringLiteral(_) => write!(f, "a string literal"),
-            Token::IntegerLiteral(_, _) => write!(f, "an integer literal"),
+            Token::IntegerLiteral(..) => write!(f, "an integer literal"),
             Token::FloatLiteral(_, _) => write!(f, "a float literal"),
             Token::CharLiteral(_) => write!(f, "a char literal"),
             Token::BooleanLiteral(_) => write!(f, "a boolean literal"),
@@ -345,3 +369,34 @@ impl Display for Token {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::IntegerKind::*;
+
+    #[test]
+    fn can_hold_literal_at_the_boundaries_of_each_kind() {
+        assert!(U8.can_hold_literal(255, false));
+        assert!(!U8.can_hold_literal(256, false));
+        assert!(I8.can_hold_literal(127, false));
+        assert!(!I8.can_hold_literal(128, false));
+        assert!(I8.can_hold_literal(-128i64 as u64, true));
+        assert!(!I8.can_hold_literal(-129i64 as u64, true));
+
+        assert!(U64.can_hold_literal(u64::MAX, false));
+        assert!(Usz.can_hold_literal(u64::MAX, false));
+        assert!(I64.can_hold_literal(i64::MAX as u64, false));
+        assert!(!I64.can_hold_literal(i64::MAX as u64 + 1, false));
+        assert!(I64.can_hold_literal(i64::MIN as u64, true));
+    }
+
+    #[test]
+    fn can_hold_literal_distinguishes_negative_literals_from_large_ones() {
+        // -1 and u64::MAX are both stored as u64::MAX
+        assert!(I8.can_hold_literal(u64::MAX, true));
+        assert!(!I8.can_hold_literal(u64::MAX, false));
+        assert!(!U8.can_hold_literal(u64::MAX, false));
+        assert!(!U64.can_hold_literal(u64::MAX, true));
+        assert!(U8.can_hold_literal(0, true));
+    }
+}
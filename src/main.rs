@@ -19,7 +19,10 @@ mod lexer;
 #[macro_use]
 mod util;
 
+// Much of the error module's API is for tools built on the library rather than the
+// compiler itself, such as converting messages into LSP diagnostics or SARIF logs.
 #[macro_use]
+#[allow(dead_code)]
 mod error;
 mod cache;
 mod cli;
@@ -35,6 +38,7 @@ mod types;
 mod llvm;
 
 use cache::ModuleCache;
+use error::theme::Theme;
 use lexer::Lexer;
 use nameresolution::NameResolver;
 
@@ -44,7 +48,7 @@ use std::fs::File;
 use std::io::{stdout, BufReader, Read};
 use std::path::Path;
 
-use crate::cli::{Backend, Cli, Completions, EmitTarget};
+use crate::cli::{Backend, Cli, Completions, EmitTarget, Explain};
 
 #[global_allocator]
 static ALLOCATOR: mimalloc::MiMalloc = mimalloc::MiMalloc;
@@ -81,6 +85,14 @@ fn print_completions<G: clap_cmp::Generator>(gen: G) {
     clap_cmp::generate(gen, &mut cmd, name, &mut stdout());
 }
 
+/// Called when the "--explain <code>" command-line flag is given.
+fn print_explanation(code: &str) {
+    match error::codes::explain(&code.to_uppercase()) {
+        Some(explanation) => println!("{}", explanation),
+        None => eprintln!("No such error code '{}'. Error codes look like E0001", code),
+    }
+}
+
 /// Convenience macro for unwrapping a Result or printing an error message and returning () on Err.
 macro_rules! expect {( $result:expr , $fmt_string:expr $( , $($msg:tt)* )? ) => ({
     match $result {
@@ -92,27 +104,67 @@ macro_rules! expect {( $result:expr , $fmt_string:expr $( , $($msg:tt)* )? ) =>
     }
 });}
 
+/// Print the summary of how many errors and warnings were issued
+fn emit_summary(cache: &ModuleCache) {
+    error::emit_summary(cache.message_counts(), &mut std::io::stderr().lock()).unwrap();
+}
+
 pub fn main() {
     if let Ok(Completions { shell_completion }) = Completions::try_parse() {
         print_completions(shell_completion);
+    } else if let Ok(Explain { explain }) = Explain::try_parse() {
+        print_explanation(&explain);
     } else {
         compile(Cli::parse())
     }
 }
 
 fn compile(args: Cli) {
-    // Setup the cache and read from the first file
-    let filename = Path::new(&args.file);
-    let file = File::open(filename);
-    let file = expect!(file, "Could not open file {}\n", filename.display());
-
-    let mut cache = ModuleCache::new(filename.parent().unwrap());
-
-    let mut reader = BufReader::new(file);
+    // Setup the cache and read from the first file. Standard input has no path so when
+    // the file is '-' its contents are given the virtual file name `<stdin>` instead.
     let mut contents = String::new();
-    expect!(reader.read_to_string(&mut contents), "Failed to read {} into a string\n", filename.display());
+    let filename = if args.file == "-" {
+        expect!(std::io::stdin().read_to_string(&mut contents), "Failed to read from stdin\n");
+        Path::new("<stdin>")
+    } else {
+        let filename = Path::new(&args.file);
+        let file = File::open(filename);
+        let file = expect!(file, "Could not open file {}\n", filename.display());
 
-    error::color_output(!args.no_color);
+        let mut reader = BufReader::new(file);
+        expect!(reader.read_to_string(&mut contents), "Failed to read {} into a string\n", filename.display());
+        filename
+    };
+
+    let mut cache = ModuleCache::new(filename.parent().unwrap());
+    error::cache_source(filename, &contents);
+
+    error::color_output(!args.no_color && error::should_color_by_default());
+    if let Some(theme) = args.theme.as_deref().and_then(Theme::builtin) {
+        error::set_theme(theme);
+    } else if let Some(theme_path) = &args.theme {
+        let theme = expect!(std::fs::read_to_string(theme_path), "Could not read theme file {}\n", theme_path);
+        match Theme::from_theme_file(&theme) {
+            Ok(theme) => error::set_theme(theme),
+            Err(message) => {
+                eprintln!("Invalid theme file {}: {}", theme_path, message);
+                return;
+            },
+        }
+    }
+    error::always_underline(args.always_underline);
+    error::caret_style(args.caret_style);
+    error::indicator_chars(args.caret_char, args.underline_char);
+    error::hyperlinks(args.hyperlinks);
+    error::diff_types(args.diff_types);
+    error::max_type_depth(args.max_type_depth);
+    error::max_line_width(args.max_line_width);
+    error::tab_width(args.tab_width);
+    error::context_lines(args.context_lines);
+    error::warnings_as_errors(args.warnings_as_errors);
+    error::message_format(args.message_format);
+    error::min_severity(args.min_severity);
+    error::path_root(std::env::current_dir().ok());
     util::timing::time_passes(args.show_time);
 
     // Phase 1: Lexing
@@ -126,7 +178,10 @@ fn compile(args: Cli) {
 
     // Phase 2: Parsing
     util::timing::start_time("Parsing");
-    let root = expect!(parser::parse(&tokens), "");
+    let root = match parser::parse(&tokens) {
+        Ok(root) => root,
+        Err(_) => return emit_summary(&cache),
+    };
 
     if args.parse {
         println!("{}", root);
@@ -136,7 +191,9 @@ fn compile(args: Cli) {
     // Phase 3: Name resolution
     // Timing for name resolution is within the start method to
     // break up the declare and define passes
-    expect!(NameResolver::start(root, &mut cache), "");
+    if NameResolver::start(root, &mut cache).is_err() {
+        return emit_summary(&cache);
+    }
 
     // Phase 4: Type inference
     util::timing::start_time("Type Inference");
@@ -147,7 +204,8 @@ fn compile(args: Cli) {
         print_definition_types(&cache);
     }
 
-    if args.check || error::get_error_count() != 0 {
+    emit_summary(&cache);
+    if args.check || cache.error_count() != 0 {
         return;
     }
 
@@ -37,7 +37,7 @@
 //!   - `trait_info: Option<TraitInfoId>` for `ast::TraitDefinition`s and `ast::TraitImpl`s
 //!   - `impl_id: Option<ImplInfoId>` for `ast::TraitImpl`s
 //!   - `module_id: Option<ModuleId>` for `ast::Import`s,
-use crate::cache::{DefinitionInfoId, EffectInfoId, ModuleCache, ModuleId};
+use crate::cache::{AssignmentState, DefinitionInfoId, EffectInfoId, ModuleCache, ModuleId};
 use crate::cache::{DefinitionKind, ImplInfoId, TraitInfoId};
 use crate::error::{
     self,
@@ -52,12 +52,12 @@ use crate::types::{
     Field, FunctionType, GeneralizedType, LetBindingLevel, PrimitiveType, Type, TypeConstructor, TypeInfoBody,
     TypeInfoId, TypeVariableId, INITIAL_LEVEL, STRING_TYPE,
 };
-use crate::util::{fmap, timing, trustme};
+use crate::util::{closest_match, fmap, timing, to_snake_case, trustme};
 
 use colored::Colorize;
 
 use std::borrow::Cow;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
@@ -84,6 +84,16 @@ pub enum NameResolutionState {
     Defined,
 }
 
+/// How the variable currently being resolved is used. Variables read with `@` or
+/// assigned to with `:=` can't be aliased through that use, so their assignments
+/// can still be tracked to warn about values which are never read.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum VariableUse {
+    Assigned,
+    Dereferenced,
+    Other,
+}
+
 /// The NameResolver struct contains the context needed for resolving
 /// a single file of the program. There is 1 NameResolver per file, and
 /// different files may be in different `NameResolutionState`s.
@@ -111,6 +121,11 @@ pub struct NameResolver {
     /// module scopes to look up definitions and types from
     pub module_scopes: HashMap<ModuleId, Scope>,
 
+    /// The number of uses of the symbols imported from each module at the time
+    /// it was imported, as given by `Scope::count_uses`. If this is unchanged
+    /// once this module is defined, the import was unused.
+    import_uses: HashMap<ModuleId, Option<u32>>,
+
     /// Type variable scopes are separate from other scopes since in general
     /// type variables do not follow normal scoping rules. For example, in the trait:
     ///
@@ -151,6 +166,9 @@ pub struct NameResolver {
     /// Keeps track of all the definitions collected within a pattern so they
     /// can all be tagged with the expression they were defined as later
     definitions_collected: Vec<DefinitionInfoId>,
+
+    /// How the variable currently being resolved is used
+    variable_use: VariableUse,
 }
 
 impl PartialEq for NameResolver {
@@ -159,6 +177,25 @@ impl PartialEq for NameResolver {
     }
 }
 
+/// Warn if a variable is not named in snake_case, the convention for variables and functions
+fn check_naming_convention<'c>(name: &str, location: Location<'c>) {
+    let starts_lowercase = name.starts_with(|c: char| c.is_lowercase() || c == '_');
+
+    if starts_lowercase && name.chars().any(char::is_uppercase) {
+        let suggestion = to_snake_case(name);
+        let warning = make_warning!(location, "`{}` should be snake_case", name);
+        let message = format!("consider renaming to `{}`", suggestion);
+        warning.with_suggestion(message, location, suggestion).issue();
+    }
+}
+
+/// Issue an error for a name defined more than once in the same scope, pointing out
+/// where it was originally defined.
+fn issue_already_defined_error<'c>(name: &str, location: Location<'c>, previous_location: Location<'c>) {
+    let error = make_error!(AlreadyDefined; location, "`{}` is defined multiple times", name);
+    error.with_label(previous_location, format!("`{}` previously defined here", name)).issue();
+}
+
 macro_rules! lookup_fn {
     ( $name:ident , $stack_field:ident , $cache_field:ident, $return_type:ty ) => {
         fn $name<'c>(&self, name: &str, cache: &mut ModuleCache<'c>) -> Option<$return_type> {
@@ -196,7 +233,7 @@ impl NameResolver {
 
         for stack in current_function_scope.iter().rev() {
             if let Some(&id) = stack.definitions.get(name) {
-                cache.definition_infos[id.0].uses += 1;
+                self.mark_used(id, cache);
                 return Some(id);
             }
         }
@@ -226,9 +263,9 @@ impl NameResolver {
         for (i, stack) in function_scope.iter().enumerate().rev() {
             if i == 0 {
                 // Definition is globally visible, no need to create a closure
-                if let Some(id) = self.global_scope().definitions.get(name) {
-                    cache.definition_infos[id.0].uses += 1;
-                    return Some(*id);
+                if let Some(&id) = self.global_scope().definitions.get(name) {
+                    self.mark_used(id, cache);
+                    return Some(id);
                 }
             } else if let Some(&from) = stack.definitions.get(name) {
                 return Some(self.create_closure(from, name, global_index, location, cache));
@@ -238,6 +275,19 @@ impl NameResolver {
         None
     }
 
+    /// Count a use of the given definition and record whether it reads the
+    /// last value assigned to it.
+    fn mark_used(&self, id: DefinitionInfoId, cache: &mut ModuleCache) {
+        let info = &mut cache.definition_infos[id.0];
+        info.uses += 1;
+
+        match (self.variable_use, info.assignment_state) {
+            (VariableUse::Assigned, _) | (_, AssignmentState::Untracked) => (),
+            (VariableUse::Dereferenced, _) => info.assignment_state = AssignmentState::Read,
+            (VariableUse::Other, _) => info.assignment_state = AssignmentState::Untracked,
+        }
+    }
+
     /// Adds a given environment variable (along with its name and the self.scopes index of the function it
     /// was found in) to a function, thus marking that function as being a closure. This works by
     /// creating a new parameter in the current function and creating a mapping between the
@@ -254,6 +304,9 @@ impl NameResolver {
         let mut ret = None;
         cache.definition_infos[environment.0].uses += 1;
 
+        // The closure may read or assign to the variable at any point after it is created
+        cache.definition_infos[environment.0].assignment_state = AssignmentState::Untracked;
+
         // Traverse through each function from where the environment variable is defined
         // to the closure that uses it, and add the environment variable to each closure.
         // Usually, this is only one function but in cases like
@@ -330,7 +383,7 @@ impl NameResolver {
         let top = self.type_variable_scopes.len() - 1;
 
         if self.type_variable_scopes[top].push_existing_type_variable(key.to_owned(), id).is_none() {
-            error!(location, "Type variable '{}' is already in scope", key);
+            error!(AlreadyDefined; location, "Type variable '{}' is already in scope", key);
         }
         id
     }
@@ -345,6 +398,7 @@ impl NameResolver {
     ) {
         if warn_unused {
             self.current_scope().check_for_unused_definitions(cache, id_to_ignore);
+            self.current_scope().check_for_unread_assignments(cache);
         }
         self.function_scopes().pop();
     }
@@ -387,6 +441,22 @@ impl NameResolver {
         self.let_binding_level = LetBindingLevel(self.let_binding_level.0 - 1);
     }
 
+    /// Warn if a new local definition shadows one from an enclosing scope of the same function,
+    /// e.g. within an `if` or `match` branch. This is likely a mistake since it looks like
+    /// it reassigns the outer definition when it does not. Rebinding a name within the same
+    /// scope, e.g. `x = x + 1`, or shadowing a definition from an enclosing function, such as
+    /// with the parameters of a `loop`, is common and not warned about.
+    fn check_for_shadowing<'c>(&mut self, name: &str, location: Location<'c>, cache: &ModuleCache<'c>) {
+        let function_scopes = self.function_scopes();
+        let enclosing_scopes = &function_scopes.scopes[..function_scopes.scopes.len() - 1];
+
+        if let Some(previous) = enclosing_scopes.iter().rev().find_map(|scope| scope.definitions.get(name)) {
+            let previous_location = cache.definition_infos[previous.0].location;
+            let warning = make_warning!(location, "`{}` shadows an earlier binding", name);
+            warning.with_label(previous_location, "previous binding here").issue();
+        }
+    }
+
     /// Checks that the given variable name is required by the trait for the impl we're currently resolving.
     /// If it is not, an error is issued that the impl does not need to implement this function.
     /// Otherwise, the name is removed from the list of required_definitions for the impl.
@@ -396,7 +466,7 @@ impl NameResolver {
             required_definitions.swap_remove(index);
         } else {
             let trait_info = &cache.trait_infos[self.current_trait.unwrap().0];
-            error!(location, "{} is not required by {}", name, trait_info.name);
+            error!(ImplDefinitionMismatch; location, "{} is not required by {}", name, trait_info.name);
         }
     }
 
@@ -426,13 +496,14 @@ impl NameResolver {
         if let Some(existing_definition) = self.current_scope().definitions.get(name) {
             // disallow shadowing in global scopes
             if in_global_scope {
-                error!(location, "{} is already in scope", name);
                 let previous_location = cache.definition_infos[existing_definition.0].location;
-                note!(previous_location, "{} previously defined here", name);
+                issue_already_defined_error(name, location, previous_location);
             } else {
                 // allow shadowing in local scopes
                 self.current_scope().check_for_unused_definitions(cache, None);
             }
+        } else if !in_global_scope && !name.starts_with('_') {
+            self.check_for_shadowing(name, location, cache);
         }
 
         if self.required_definitions.is_some() {
@@ -462,9 +533,8 @@ impl NameResolver {
         &mut self, name: String, args: Vec<TypeVariableId>, cache: &mut ModuleCache<'c>, location: Location<'c>,
     ) -> TypeInfoId {
         if let Some(existing_definition) = self.current_scope().types.get(&name) {
-            error!(location, "{} is already in scope", name);
             let previous_location = cache.type_infos[existing_definition.0].locate();
-            note!(previous_location, "{} previously defined here", name);
+            issue_already_defined_error(&name, location, previous_location);
         }
 
         let id = cache.push_type_info(name.clone(), args, location);
@@ -480,9 +550,8 @@ impl NameResolver {
         node: &'c mut ast::TraitDefinition<'c>, cache: &mut ModuleCache<'c>, location: Location<'c>,
     ) -> TraitInfoId {
         if let Some(existing_definition) = self.current_scope().traits.get(&name) {
-            error!(location, "{} is already in scope", name);
             let previous_location = cache.trait_infos[existing_definition.0].locate();
-            note!(previous_location, "{} previously defined here", name);
+            issue_already_defined_error(&name, location, previous_location);
         }
 
         let id = cache.push_trait_definition(name.clone(), args, fundeps, Some(node), location);
@@ -498,9 +567,8 @@ impl NameResolver {
         cache: &mut ModuleCache<'c>, location: Location<'c>,
     ) -> EffectInfoId {
         if let Some(existing_definition) = self.current_scope().effects.get(&name) {
-            error!(location, "{} is already in scope", name);
             let previous_location = cache.effect_infos[existing_definition.0].locate();
-            note!(previous_location, "{} previously defined here", name);
+            issue_already_defined_error(&name, location, previous_location);
         }
 
         let id = cache.push_effect_definition(name.clone(), args, node, location);
@@ -548,8 +616,8 @@ impl NameResolver {
         let expected = self.get_expected_type_argument_count(constructor, cache);
         if args.len() != expected && !matches!(constructor, Type::TypeVariable(_)) {
             let plural_s = if expected == 1 { "" } else { "s" };
-            let is_are = if args.len() == 1 { "is" } else { "are" };
-            error!(location, "Type {} expects {} argument{}, but {} {} given here", constructor.display(cache), expected, plural_s, args.len(), is_are);
+            let was_were = if args.len() == 1 { "was" } else { "were" };
+            error!(WrongTypeArgumentCount; location, "Type `{}` takes {} type argument{}, but {} {} supplied", constructor.display(cache), expected, plural_s, args.len(), was_were);
         }
 
         // Check argument is an integer/float type (issue #146)
@@ -557,12 +625,12 @@ impl NameResolver {
             match constructor {
                 Type::Primitive(PrimitiveType::IntegerType) => {
                     if !matches!(first_arg, Type::Primitive(PrimitiveType::IntegerTag(_)) | Type::TypeVariable(_)) {
-                        error!(location, "Type {} is not an integer type", first_arg.display(cache));
+                        error!(InvalidPrimitiveArgument; location, "Type {} is not an integer type", first_arg.display(cache));
                     }
                 },
                 Type::Primitive(PrimitiveType::FloatType) => {
                     if !matches!(first_arg, Type::Primitive(PrimitiveType::FloatTag(_)) | Type::TypeVariable(_)) {
-                        error!(location, "Type {} is not a float type", first_arg.display(cache));
+                        error!(InvalidPrimitiveArgument; location, "Type {} is not a float type", first_arg.display(cache));
                     }
                 }
                 _ => (),
@@ -584,6 +652,7 @@ impl NameResolver {
             Type::Ref(_) => 1,
             Type::Struct(_, _) => 0,
             Type::Effects(_) => 0,
+            Type::Error => 0,
         }
     }
 
@@ -612,7 +681,7 @@ impl<'c> NameResolver {
         timing::start_time("Name Resolution (Define)");
         resolver.define(cache);
 
-        if error::get_error_count() != 0 {
+        if cache.error_count() != 0 {
             Err(())
         } else {
             Ok(())
@@ -633,6 +702,7 @@ impl<'c> NameResolver {
 
         let mut resolver = NameResolver {
             module_scopes: HashMap::new(),
+            import_uses: HashMap::new(),
             filepath: filepath.to_owned(),
             scopes: vec![FunctionScopes::new()],
             exports: Scope::new(cache),
@@ -643,6 +713,7 @@ impl<'c> NameResolver {
             required_definitions: None,
             current_function: None,
             definitions_collected: vec![],
+            variable_use: VariableUse::Other,
             let_binding_level: LetBindingLevel(INITIAL_LEVEL),
             module_id,
         };
@@ -664,6 +735,12 @@ impl<'c> NameResolver {
         resolver
     }
 
+    /// True if this resolver is for the module being compiled rather than one it imports.
+    /// That module is always declared first by `NameResolver::start`.
+    fn is_root_module(&self) -> bool {
+        self.module_id == ModuleId(0)
+    }
+
     /// Performs the define pass on the current NameResolver, linking all
     /// variables to their definition, filling in each XXXInfoId field, etc.
     /// See the module-level comment for more details on the define pass.
@@ -675,6 +752,31 @@ impl<'c> NameResolver {
         self.state = NameResolutionState::DefineInProgress;
         ast.define(self, cache);
         self.state = NameResolutionState::Defined;
+
+        self.check_for_unused_imports(ast, cache);
+    }
+
+    /// Issue a warning for each import in this module whose symbols were never used.
+    fn check_for_unused_imports(&self, ast: &Ast<'c>, cache: &ModuleCache<'c>) {
+        let statements = match ast {
+            Ast::Sequence(sequence) => &sequence.statements[..],
+            other => std::slice::from_ref(other),
+        };
+
+        for statement in statements {
+            if let Ast::Import(import) = statement {
+                if let Some(module_id) = import.module_id {
+                    let exports = &cache.name_resolvers.get_mut(module_id.0).unwrap().exports;
+                    let uses = self.import_uses.get(&module_id).copied().flatten();
+
+                    if uses.is_some() && uses == exports.count_uses(cache, &import.symbols) {
+                        let path = import.path.join(".");
+                        let warning = make_warning!(import.location, "Unused import `{}`", path);
+                        warning.with_suggestion("remove the import", import.location, "").issue();
+                    }
+                }
+            }
+        }
     }
 
     /// Converts an ast::Type to a types::Type, expects all typevars to be in scope
@@ -708,7 +810,7 @@ impl<'c> NameResolver {
                         let id = self.push_new_type_variable(name, *location, cache);
                         Type::TypeVariable(id)
                     } else {
-                        error!(*location, "Type variable {} was not found in scope", name);
+                        error!(NotFound; *location, "Type variable {} was not found in scope", name);
                         Type::UNIT
                     }
                 },
@@ -716,7 +818,16 @@ impl<'c> NameResolver {
             ast::Type::UserDefined(name, location) => match self.lookup_type(name, cache) {
                 Some(id) => Type::UserDefined(id),
                 None => {
-                    error!(*location, "Type {} was not found in scope", name);
+                    let function_scope = self.scopes.last().unwrap().iter();
+                    let scopes = function_scope.chain(std::iter::once(self.global_scope()));
+                    let candidates = scopes.flat_map(|scope| scope.types.keys().map(String::as_str));
+
+                    match closest_match(name, candidates) {
+                        Some(candidate) => {
+                            error!(NotFound; *location, "Type `{}` is not defined, did you mean `{}`?", name, candidate)
+                        },
+                        None => error!(NotFound; *location, "Type `{}` is not defined", name),
+                    }
                     Type::UNIT
                 },
             },
@@ -732,7 +843,7 @@ impl<'c> NameResolver {
                 let pair = match self.lookup_type(&Token::Comma.to_string(), cache) {
                     Some(id) => Type::UserDefined(id),
                     None => {
-                        error!(*location, "The pair type (`,`) was not found in scope, there may have been a problem while importing the prelude");
+                        error!(NotFound; *location, "The pair type (`,`) was not found in scope, there may have been a problem while importing the prelude");
                         Type::UNIT
                     },
                 };
@@ -762,7 +873,11 @@ impl<'c> NameResolver {
         ast.declare(self, cache);
         self.auto_declare = false;
         for id in self.definitions_collected.iter() {
-            cache.definition_infos[id.0].definition = Some(definition());
+            let info = &mut cache.definition_infos[id.0];
+            info.definition = Some(definition());
+            if self.is_root_module() {
+                check_naming_convention(&info.name, info.location);
+            }
         }
     }
 
@@ -838,7 +953,11 @@ impl<'c> NameResolver {
         }
         self.auto_declare = false;
         for id in self.definitions_collected.iter() {
-            cache.definition_infos[id.0].definition = Some(definition());
+            let info = &mut cache.definition_infos[id.0];
+            info.definition = Some(definition());
+            if self.is_root_module() {
+                check_naming_convention(&info.name, info.location);
+            }
         }
     }
 
@@ -854,7 +973,7 @@ impl<'c> NameResolver {
                     id: cache.next_trait_constraint_id(),
                 });
             } else {
-                error!(trait_.location, "Could not find trait {} in scope", trait_.name.blue());
+                error!(NotFound; trait_.location, "Could not find trait {} in scope", trait_.name.blue());
             }
         }
         required_traits
@@ -954,17 +1073,20 @@ impl<'c> Resolvable<'c> for ast::Variable<'c> {
 
                     if let Some(module_id) = module_id {
                         self.definition = resolver.module_scopes[&module_id].definitions.get(name.as_ref()).copied();
+                        if let Some(id) = self.definition {
+                            cache.definition_infos[id.0].uses += 1;
+                        }
                         self.impl_scope = Some(resolver.current_scope().impl_scope);
                         self.id = Some(cache.push_variable(name.into_owned(), self.location));
                     } else {
-                        error!(self.location, "Could not find module `{}`", relative_path);
+                        error!(NotFound; self.location, "Could not find module `{}`", relative_path);
                     }
                 }
             }
 
             // If it is still not declared, print an error
             if self.definition.is_none() {
-                error!(self.location, "No declaration for `{}` was found in scope", self);
+                error!(NotFound; self.location, "Cannot find value `{}` in this scope", self);
             }
         }
     }
@@ -1000,9 +1122,15 @@ impl<'c> Resolvable<'c> for ast::FunctionCall<'c> {
         self.function.define(resolver, cache);
         resolver.auto_declare = prev_auto_declare;
 
+        if self.is_dereference_of_variable() {
+            resolver.variable_use = VariableUse::Dereferenced;
+        }
+
         for arg in self.args.iter_mut() {
             arg.define(resolver, cache)
         }
+
+        resolver.variable_use = VariableUse::Other;
     }
 }
 
@@ -1032,6 +1160,14 @@ impl<'c> Resolvable<'c> for ast::Definition<'c> {
 
         resolver.resolve_definitions(self.pattern.as_mut(), cache, definition);
 
+        // Assignments are only tracked for local variables since globals may be read by any
+        // function, regardless of where that function is called.
+        if let Ast::Variable(ast::Variable { definition: Some(id), .. }) = self.pattern.as_ref() {
+            if self.mutable && !resolver.in_global_scope() {
+                cache.definition_infos[id.0].assignment_state = AssignmentState::Read;
+            }
+        }
+
         self.level = Some(resolver.let_binding_level);
 
         resolver.try_set_current_function(self);
@@ -1148,6 +1284,87 @@ fn create_fields<'c>(vec: &Fields<'c>, resolver: &mut NameResolver, cache: &mut
     })
 }
 
+/// Issue an error if the type with the given id contains itself without any indirection
+/// through a pointer, reference, or function. Such a type would be infinitely large.
+/// Each element of `contents` is a field or variant argument of the type along with
+/// the Ast it was converted from, so the error can suggest where to add a reference.
+fn check_for_infinite_size<'a, 'c: 'a>(
+    id: TypeInfoId, contents: impl Iterator<Item = (&'a ast::Type<'c>, &'a Type)>, cache: &ModuleCache<'c>,
+) {
+    for (ast, typ) in contents {
+        if occurs_unboxed(typ, UnboxedTarget::Type(id), &mut HashSet::new(), cache) {
+            let name = &cache[id].name;
+            let error = make_error!(InfiniteSize; ast.locate(), "Recursive type `{}` has infinite size", name)
+                .with_note(format!("`{}` would need to be large enough to contain another `{}`", name, name));
+            // Compound types are already displayed with surrounding parenthesis
+            let error = error.with_suggestion("add indirection with a `Ref`", ast.locate(), format!("Ref {}", ast));
+            error.issue();
+            return;
+        }
+    }
+}
+
+/// What `occurs_unboxed` searches for
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum UnboxedTarget {
+    /// A use of the user-defined type with the given id
+    Type(TypeInfoId),
+
+    /// A use of the given type parameter
+    Variable(TypeVariableId),
+}
+
+/// True if `target` occurs anywhere within the given type, other than behind a pointer,
+/// reference, or function. Any user-defined types are searched through as well, using
+/// `visited` to avoid searching the same type twice.
+fn occurs_unboxed(typ: &Type, target: UnboxedTarget, visited: &mut HashSet<TypeInfoId>, cache: &ModuleCache) -> bool {
+    match typ {
+        Type::Primitive(_) | Type::Function(_) | Type::Ref(_) | Type::Effects(_) | Type::Error => false,
+        Type::TypeVariable(id) => target == UnboxedTarget::Variable(*id),
+        Type::UserDefined(id) => {
+            target == UnboxedTarget::Type(*id) || body_contains_unboxed(*id, target, visited, cache)
+        },
+        Type::Struct(fields, _) => fields.values().any(|field| occurs_unboxed(field, target, visited, cache)),
+        Type::TypeApplication(constructor, args) => match constructor.as_ref() {
+            Type::Primitive(PrimitiveType::Ptr) | Type::Ref(_) => false,
+            // An argument is only contained in the type if the corresponding parameter is
+            Type::UserDefined(id) => {
+                target == UnboxedTarget::Type(*id)
+                    || body_contains_unboxed(*id, target, visited, cache)
+                    || args.iter().zip(&cache[*id].args).any(|(arg, parameter)| {
+                        let parameter = UnboxedTarget::Variable(*parameter);
+                        occurs_unboxed(arg, target, visited, cache)
+                            && body_contains_unboxed(*id, parameter, &mut HashSet::new(), cache)
+                    })
+            },
+            constructor => {
+                occurs_unboxed(constructor, target, visited, cache)
+                    || args.iter().any(|arg| occurs_unboxed(arg, target, visited, cache))
+            },
+        },
+    }
+}
+
+/// True if `target` occurs unboxed within the definition of the given type
+fn body_contains_unboxed(
+    id: TypeInfoId, target: UnboxedTarget, visited: &mut HashSet<TypeInfoId>, cache: &ModuleCache,
+) -> bool {
+    if !visited.insert(id) {
+        return false;
+    }
+
+    match &cache[id].body {
+        TypeInfoBody::Union(variants) => {
+            variants.iter().flat_map(|variant| &variant.args).any(|arg| occurs_unboxed(arg, target, visited, cache))
+        },
+        TypeInfoBody::Struct(fields) => {
+            fields.iter().any(|field| occurs_unboxed(&field.field_type, target, visited, cache))
+        },
+        TypeInfoBody::Alias(typ) => occurs_unboxed(typ, target, visited, cache),
+        TypeInfoBody::Unknown => false,
+    }
+}
+
 impl<'c> Resolvable<'c> for ast::TypeDefinition<'c> {
     fn declare(&mut self, resolver: &mut NameResolver, cache: &mut ModuleCache<'c>) {
         let args = fmap(&self.args, |_| cache.next_type_variable_id(resolver.let_binding_level));
@@ -1171,8 +1388,12 @@ impl<'c> Resolvable<'c> for ast::TypeDefinition<'c> {
         match &self.definition {
             ast::TypeDefinitionBody::Union(vec) => {
                 let variants = create_variants(vec, type_id, resolver, cache);
+                let arg_types = variants.iter().flat_map(|variant| &variant.args).cloned().collect::<Vec<_>>();
                 let type_info = &mut cache.type_infos[type_id.0];
                 type_info.body = TypeInfoBody::Union(variants);
+
+                let arg_asts = vec.iter().flat_map(|(_, args, _)| args);
+                check_for_infinite_size(type_id, arg_asts.zip(&arg_types), cache);
             },
             ast::TypeDefinitionBody::Struct(vec) => {
                 let fields = create_fields(vec, resolver, cache);
@@ -1180,6 +1401,7 @@ impl<'c> Resolvable<'c> for ast::TypeDefinition<'c> {
 
                 let type_info = &mut cache.type_infos[type_id.0];
                 type_info.body = TypeInfoBody::Struct(fields);
+                check_for_infinite_size(type_id, vec.iter().map(|(_, typ, _)| typ).zip(&field_types), cache);
 
                 // Create the constructor for this type.
                 // This is done inside create_variants for tagged union types
@@ -1232,7 +1454,7 @@ pub fn declare_module<'a>(path: &Path, cache: &mut ModuleCache<'a>, error_locati
     let (file, path) = match find_file(path, cache) {
         Some((f, p)) => (f, p),
         _ => {
-            error!(error_location, "Couldn't open file for import: {}.an", path.display());
+            error!(ImportFailed; error_location, "Couldn't open file for import: {}.an", path.display());
             return None;
         },
     };
@@ -1252,6 +1474,7 @@ pub fn declare_module<'a>(path: &Path, cache: &mut ModuleCache<'a>, error_locati
     let mut reader = BufReader::new(file);
     let mut contents = String::new();
     reader.read_to_string(&mut contents).unwrap();
+    error::cache_source(path, &contents);
 
     timing::start_time("Lexing");
     let tokens = Lexer::new(path, &contents).collect::<Vec<_>>();
@@ -1302,6 +1525,8 @@ impl<'c> Resolvable<'c> for ast::Import<'c> {
             if let Some(exports) = define_module(module_id, cache, self.location) {
                 // import only the imported symbols
                 resolver.current_scope().import(exports, cache, self.location, &self.symbols);
+                let uses = exports.count_uses(cache, &self.symbols);
+                resolver.import_uses.entry(module_id).or_insert(uses);
                 // add the module scope itself
                 resolver.module_scopes.insert(module_id, exports.to_owned());
             }
@@ -1374,7 +1599,7 @@ impl<'c> Resolvable<'c> for ast::TraitImpl<'c> {
         let trait_id = match &self.trait_info {
             Some(id) => *id,
             None => {
-                error!(self.location, "Trait {} was not found in scope", self.trait_name);
+                error!(NotFound; self.location, "Trait {} was not found in scope", self.trait_name);
                 return;
             },
         };
@@ -1391,7 +1616,7 @@ impl<'c> Resolvable<'c> for ast::TraitImpl<'c> {
         let required_arg_count = trait_info.typeargs.len() + trait_info.fundeps.len();
         if self.trait_args.len() != required_arg_count {
             error!(
-                self.location,
+                WrongTypeArgumentCount; self.location,
                 "impl has {} type arguments but {} requires {}",
                 self.trait_args.len(),
                 self.trait_name.blue(),
@@ -1410,7 +1635,7 @@ impl<'c> Resolvable<'c> for ast::TraitImpl<'c> {
         // be moved here instead
         for required_definition in resolver.required_definitions.as_ref().unwrap() {
             error!(
-                self.location,
+                ImplDefinitionMismatch; self.location,
                 "impl is missing a definition for {}", cache.definition_infos[required_definition.0].name
             );
         }
@@ -1462,6 +1687,18 @@ impl<'c> Resolvable<'c> for ast::Sequence<'c> {
         for statement in self.statements.iter_mut() {
             statement.define(resolver, cache)
         }
+
+        // Only the first statement after a `return` is pointed out, it implies the rest are unreachable.
+        // A statement sharing the location of its sequence was inserted by desugaring an `if` without
+        // an `else`, and isn't written by the user.
+        if let Some(index) = self.statements.iter().position(|statement| matches!(statement, Ast::Return(_))) {
+            let next = self.statements.get(index + 1);
+            if let Some(unreachable) = next.filter(|statement| statement.locate() != self.location) {
+                let warning = make_warning!(unreachable.locate(), "This code is unreachable");
+                let label = "any code following this expression is unreachable";
+                warning.with_label(self.statements[index].locate(), label).issue();
+            }
+        }
     }
 }
 
@@ -1497,8 +1734,20 @@ impl<'c> Resolvable<'c> for ast::Assignment<'c> {
     fn declare(&mut self, _resolver: &mut NameResolver, _cache: &mut ModuleCache<'c>) {}
 
     fn define(&mut self, resolver: &mut NameResolver, cache: &mut ModuleCache<'c>) {
+        if matches!(self.lhs.as_ref(), Ast::Variable(_)) {
+            resolver.variable_use = VariableUse::Assigned;
+        }
         self.lhs.define(resolver, cache);
+        resolver.variable_use = VariableUse::Other;
+
         self.rhs.define(resolver, cache);
+
+        if let Ast::Variable(ast::Variable { definition: Some(id), location, .. }) = self.lhs.as_ref() {
+            let info = &mut cache.definition_infos[id.0];
+            if info.assignment_state != AssignmentState::Untracked {
+                info.assignment_state = AssignmentState::Unread(*location);
+            }
+        }
     }
 }
 
@@ -1529,7 +1778,7 @@ impl<'c> Resolvable<'c> for ast::EffectDefinition<'c> {
             }
 
             if !matches!(&declaration.rhs, ast::Type::Function(..)) {
-                error!(declaration.rhs.locate(), "Only function types are allowed in effect declarations");
+                error!(InvalidEffect; declaration.rhs.locate(), "Only function types are allowed in effect declarations");
             }
         }
 
@@ -1567,7 +1816,7 @@ fn get_handled_effect_function(pattern: &ast::Ast) -> Option<DefinitionInfoId> {
             Ast::Variable(variable) => variable.definition,
             _ => {
                 error!(
-                    call.function.locate(),
+                    InvalidPattern; call.function.locate(),
                     "Invalid pattern in function position, expected a variable for an effect function"
                 );
                 None
@@ -1576,7 +1825,7 @@ fn get_handled_effect_function(pattern: &ast::Ast) -> Option<DefinitionInfoId> {
         Ast::Return(_) => None,
         _ => {
             error!(
-                pattern.locate(),
+                InvalidPattern; pattern.locate(),
                 "Invalid handle pattern. Handle patterns must be an effect function call or a return expression"
             );
             None
@@ -1619,7 +1868,7 @@ impl<'c> Resolvable<'c> for ast::Handle<'c> {
                             remaining_cases.extend(cache[id].declarations.iter().copied());
                             remaining_cases.remove(&case);
                         },
-                        other => error!(pattern.locate(), "{} is not an effect: {:?}", info.name, other),
+                        other => error!(InvalidEffect; pattern.locate(), "{} is not an effect: {:?}", info.name, other),
                     }
                 }
             }
@@ -1627,7 +1876,7 @@ impl<'c> Resolvable<'c> for ast::Handle<'c> {
 
         if !remaining_cases.is_empty() {
             let missing_cases = fmap(remaining_cases, |id| cache[id].name.clone()).join(", ");
-            error!(self.location, "Missing cases: {}", missing_cases);
+            error!(NonExhaustive; self.location, "Handle is not exhaustive; missing cases: {}", missing_cases);
         }
     }
 }
@@ -10,8 +10,11 @@
 //! is significant because a type variable's scope is different
 //! than the general Scope for other symbols. See the TypeVariableScope
 //! struct for more details on this.
-use crate::cache::{DefinitionInfoId, EffectInfoId, ImplInfoId, ImplScopeId, ModuleCache, ModuleId, TraitInfoId};
+use crate::cache::{AssignmentState, DefinitionInfoId, DefinitionKind, EffectInfoId, ImplInfoId, ImplScopeId};
+use crate::cache::{ModuleCache, ModuleId, TraitInfoId};
+use crate::error::collector::DiagnosticsCollector;
 use crate::error::location::{Locatable, Location};
+use crate::error::ErrorMessage;
 use crate::parser::ast;
 use crate::types::{TypeInfoId, TypeVariableId};
 use std::collections::{HashMap, HashSet};
@@ -89,8 +92,9 @@ impl Scope {
 
                     if let Some(existing) = self.$field.get(k) {
                         let prev_loc = cache.$cache_field[existing.0].locate();
-                        let error = make_error!(location, "import shadows previous definition of {}", k);
-                        let note = make_note!(prev_loc, "{} was previously defined here", k);
+                        let error = make_error!(AlreadyDefined; location, "import shadows previous definition of `{}`", k);
+                        let error = error.with_label(cache.$cache_field[v.0].locate(), "imported definition here");
+                        let note = make_note!(prev_loc, "`{}` defined here", k);
                         $errors.push((error, note));
                     } else {
                         self.$field.insert(k.clone(), *v);
@@ -108,26 +112,47 @@ impl Scope {
         if !errors.is_empty() {
             // Using sort_by instead of sort_by_key here avoids cloning the ErrorMessage
             errors.sort_by(|x, y| x.0.cmp(&y.0));
-            errors.into_iter().for_each(|(error, note)| eprintln!("{}\n{}", error, note));
+            for (error, note) in errors {
+                error.issue();
+                note.issue();
+            }
         }
     }
 
+    /// Returns the total number of uses of each definition, type, and trait that importing
+    /// `symbols` from this scope would bring into scope. Returns None if this would also import
+    /// any impls or effects since their uses aren't counted, so the import may still be needed.
+    pub fn count_uses(&self, cache: &ModuleCache, symbols: &HashSet<String>) -> Option<u32> {
+        let imported = |name: &String| symbols.is_empty() || symbols.contains(name);
+
+        if !self.impls.is_empty() || self.effects.keys().any(imported) {
+            return None;
+        }
+
+        let definitions = self.definitions.iter().filter(|(name, _)| imported(name));
+        let types = self.types.iter().filter(|(name, _)| imported(name));
+        let traits = self.traits.iter().filter(|(name, _)| imported(name));
+
+        let uses = definitions.map(|(_, id)| cache.definition_infos[id.0].uses).sum::<u32>()
+            + types.map(|(_, id)| cache.type_infos[id.0].uses).sum::<u32>()
+            + traits.map(|(_, id)| cache.trait_infos[id.0].uses).sum::<u32>();
+
+        Some(uses)
+    }
+
     /// Check for any unused definitions and issue the appropriate warnings if found.
     /// This is meant to be done at the end of a scope since if we're still in the middle
     /// of name resolution for a particular scope, any currently unused symbol may become
     /// used later on.
     pub fn check_for_unused_definitions(&self, cache: &ModuleCache, id_to_ignore: Option<DefinitionInfoId>) {
-        let mut warnings = vec![];
+        let mut warnings = DiagnosticsCollector::new();
 
         for (name, id) in &self.definitions {
             if id_to_ignore != Some(*id) {
                 let definition = &cache.definition_infos[id.0];
                 if definition.uses == 0 && !definition.ignore_unused_warning {
-                    warnings.push(make_warning!(
-                        definition.location,
-                        "{} is unused (prefix name with _ to silence this warning)",
-                        name
-                    ));
+                    let kind = unused_definition_kind(&definition.definition);
+                    warnings.push(unused_warning(kind, name, definition.location));
                 }
             }
         }
@@ -135,21 +160,54 @@ impl Scope {
         for (name, id) in &self.types {
             let definition = &cache.type_infos[id.0];
             if definition.uses == 0 && !definition.name.starts_with('_') {
-                warnings.push(make_warning!(
-                    definition.location,
-                    "{} is unused (prefix name with _ to silence this warning)",
-                    name
-                ));
+                warnings.push(unused_warning("type", name, definition.location));
             }
         }
 
-        if !warnings.is_empty() {
-            warnings.sort();
-            warnings.into_iter().for_each(|warning| eprintln!("{}", warning));
+        warnings.sort();
+        warnings.emit_all(&mut std::io::stderr()).unwrap();
+    }
+
+    /// Warn about any definition in this scope whose last assigned value is never read.
+    /// As with unused definitions, this can only be known once the scope has ended.
+    pub fn check_for_unread_assignments(&self, cache: &ModuleCache) {
+        let mut warnings = DiagnosticsCollector::new();
+
+        for (name, id) in &self.definitions {
+            let definition = &cache.definition_infos[id.0];
+            if let AssignmentState::Unread(location) = definition.assignment_state {
+                if !definition.ignore_unused_warning {
+                    warning!(warnings => location, "Value assigned to `{}` is never read", name);
+                }
+            }
         }
+
+        warnings.sort();
+        warnings.emit_all(&mut std::io::stderr()).unwrap();
     }
 }
 
+/// Describes an unused definition in its warning: "function" if it was defined
+/// as a lambda, e.g. `foo x = x`, and "variable" otherwise.
+fn unused_definition_kind(definition: &Option<DefinitionKind>) -> &'static str {
+    match definition {
+        Some(DefinitionKind::Definition(definition)) if matches!(definition.expr.as_ref(), ast::Ast::Lambda(_)) => {
+            "function"
+        },
+        _ => "variable",
+    }
+}
+
+/// Create the warning issued for an unused definition or type. `kind` describes
+/// what was defined, e.g. "variable", and is shown in the message.
+fn unused_warning<'c>(kind: &str, name: &str, location: Location<'c>) -> ErrorMessage<'c> {
+    make_warning!(location, "Unused {} `{}`", kind, name).with_suggestion(
+        "prefix the name with `_` to silence this warning",
+        location,
+        format!("_{}", name),
+    )
+}
+
 /// A TypeVariableScope is an alternative to "normal" scopes that other symbols
 /// live in. This is needed in general because type variables do not follow normal
 /// scoping rules. Consider the following trait definition:
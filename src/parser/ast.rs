@@ -36,7 +36,8 @@ use std::rc::Rc;
 
 #[derive(Clone, Debug, Eq, PartialOrd, Ord)]
 pub enum LiteralKind {
-    Integer(u64, Option<IntegerKind>),
+    /// An integer and whether it is negative, see `Token::IntegerLiteral`
+    Integer(u64, Option<IntegerKind>, bool),
     Float(u64, Option<FloatKind>),
     String(String),
     Char(char),
@@ -147,6 +148,14 @@ impl<'a> FunctionCall<'a> {
             false
         }
     }
+
+    /// True if this is a call to `@` with a single variable, e.g. `@x`
+    pub fn is_dereference_of_variable(&self) -> bool {
+        match (self.function.as_ref(), self.args.as_slice()) {
+            (Ast::Variable(function), [Ast::Variable(_)]) => function.kind == VariableKind::Operator(Token::At),
+            _ => false,
+        }
+    }
 }
 
 /// foo = 23
@@ -423,7 +432,7 @@ impl PartialEq for LiteralKind {
     fn eq(&self, other: &Self) -> bool {
         use LiteralKind::*;
         match (self, other) {
-            (Integer(x, _), Integer(y, _)) => x == y,
+            (Integer(x, ..), Integer(y, ..)) => x == y,
             (Float(x, _), Float(y, _)) => x == y,
             (String(x), String(y)) => x == y,
             (Char(x), Char(y)) => x == y,
@@ -438,7 +447,7 @@ impl std::hash::Hash for LiteralKind {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         core::mem::discriminant(self).hash(state);
         match self {
-            LiteralKind::Integer(x, _) => x.hash(state),
+            LiteralKind::Integer(x, ..) => x.hash(state),
             LiteralKind::Float(x, _) => x.hash(state),
             LiteralKind::String(x) => x.hash(state),
             LiteralKind::Char(x) => x.hash(state),
@@ -469,8 +478,8 @@ impl<'a> Ast<'a> {
         }
     }
 
-    pub fn integer(x: u64, kind: Option<IntegerKind>, location: Location<'a>) -> Ast<'a> {
-        Ast::Literal(Literal { kind: LiteralKind::Integer(x, kind), location, typ: None })
+    pub fn integer(x: u64, kind: Option<IntegerKind>, negative: bool, location: Location<'a>) -> Ast<'a> {
+        Ast::Literal(Literal { kind: LiteralKind::Integer(x, kind, negative), location, typ: None })
     }
 
     pub fn float(x: f64, kind: Option<FloatKind>, location: Location<'a>) -> Ast<'a> {
@@ -400,11 +400,11 @@ pub fn string_literal_token<'a, 'b>(input: Input<'a, 'b>) -> ParseResult<'a, 'b,
     }
 }
 
-pub fn integer_literal_token<'a, 'b>(input: Input<'a, 'b>) -> ParseResult<'a, 'b, (u64, Option<IntegerKind>)> {
+pub fn integer_literal_token<'a, 'b>(input: Input<'a, 'b>) -> ParseResult<'a, 'b, (u64, Option<IntegerKind>, bool)> {
     match input[0] {
-        (Token::IntegerLiteral(int, kind), location) => Ok((&input[1..], (int, kind), location)),
+        (Token::IntegerLiteral(int, kind, negative), location) => Ok((&input[1..], (int, kind, negative), location)),
         (Token::Invalid(c), location) => Err(ParseError::Fatal(Box::new(ParseError::LexerError(c, location)))),
-        (_, location) => Err(ParseError::Expected(vec![Token::IntegerLiteral(0, None)], location)),
+        (_, location) => Err(ParseError::Expected(vec![Token::IntegerLiteral(0, None, false)], location)),
     }
 }
 
@@ -2,7 +2,7 @@
 //! when printing this error to stderr.
 use super::combinators::Input;
 use crate::error::location::{Locatable, Location};
-use crate::error::ErrorMessage;
+use crate::error::{codes::ErrorCode, ErrorMessage};
 use crate::lexer::token::{LexerError, Token};
 use crate::util::join_with;
 use std::fmt::Display;
@@ -43,27 +43,33 @@ impl<'a> Locatable<'a> for ParseError<'a> {
     }
 }
 
-impl<'a> Display for ParseError<'a> {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        match self {
-            ParseError::Fatal(error) => error.fmt(fmt),
+impl<'a> ParseError<'a> {
+    /// Convert this error into the ErrorMessage it is issued as
+    pub fn to_error_message(&self) -> ErrorMessage<'a> {
+        let message = match self {
+            ParseError::Fatal(error) => return error.to_error_message(),
             ParseError::Expected(tokens, location) => {
                 if tokens.len() == 1 {
                     let msg = format!("parser expected {} here", tokens[0]);
-                    write!(fmt, "{}", ErrorMessage::error(&msg[..], *location))
+                    ErrorMessage::error(&msg[..], *location)
                 } else {
                     let expected = join_with(tokens.iter(), ", ");
                     let msg = format!("parser expected one of {}", expected);
-                    write!(fmt, "{}", ErrorMessage::error(&msg[..], *location))
+                    ErrorMessage::error(&msg[..], *location)
                 }
             },
             ParseError::InRule(rule, location) => {
                 let msg = format!("failed trying to parse a {}", rule);
-                write!(fmt, "{}", ErrorMessage::error(&msg[..], *location))
+                ErrorMessage::error(&msg[..], *location)
             },
-            ParseError::LexerError(error, location) => {
-                write!(fmt, "{}", ErrorMessage::error(&error.to_string()[..], *location))
-            },
-        }
+            ParseError::LexerError(error, location) => ErrorMessage::error(&error.to_string()[..], *location),
+        };
+        message.with_code(ErrorCode::Syntax)
+    }
+}
+
+impl<'a> Display for ParseError<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(fmt, "{}", self.to_error_message())
     }
 }
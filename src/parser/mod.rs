@@ -38,7 +38,7 @@ type AstResult<'a, 'b> = ParseResult<'a, 'b, Ast<'b>>;
 pub fn parse<'a, 'b>(input: Input<'a, 'b>) -> Result<Ast<'b>, ParseError<'b>> {
     let result = parse_file(input);
     if let Err(error) = &result {
-        eprintln!("{}", error);
+        error.to_error_message().issue();
     }
     result
 }
@@ -726,8 +726,8 @@ parser!(string loc =
 );
 
 parser!(integer loc =
-    (value, kind) <- integer_literal_token;
-    Ast::integer(value, kind, loc)
+    (value, kind, negative) <- integer_literal_token;
+    Ast::integer(value, kind, negative, loc)
 );
 
 parser!(float loc =
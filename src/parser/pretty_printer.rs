@@ -19,7 +19,7 @@ impl<'a> Display for ast::Literal<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         use ast::LiteralKind::*;
         match &self.kind {
-            Integer(x, _) => write!(f, "{}", x),
+            Integer(x, ..) => write!(f, "{}", x),
             Float(x, _) => write!(f, "{}", f64::from_bits(*x)),
             String(s) => write!(f, "\"{}\"", s),
             Char(c) => write!(f, "'{}'", c),
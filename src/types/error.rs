@@ -1,28 +1,77 @@
 use crate::{
     cache::ModuleCache,
-    error::{location::Location, ErrorMessage},
+    error::{self, location::Location, ErrorMessage},
+    util::closest_match,
 };
 
-use super::Type;
+use super::typeprinter::{show_type_diff, show_types};
+use super::{Type, TypeVariableId};
 
 pub fn from_template<'c>(
     template: &str, location: Location<'c>, t1: &Type, t2: &Type, cache: &ModuleCache<'c>,
 ) -> ErrorMessage<'c> {
     let mut msg = String::new();
-    let t1 = t1.display(cache);
-    let t2 = t2.display(cache);
+    let (t1, t2) = if error::should_diff_types() {
+        show_type_diff(t1, t2, cache)
+    } else {
+        (t1.display(cache).to_string(), t2.display(cache).to_string())
+    };
 
     let mut iter = template.chars();
     while let Some(c) = iter.next() {
         match c {
             '$' => match iter.next() {
-                Some('1') => msg += &t1.to_string(),
-                Some('2') => msg += &t2.to_string(),
+                Some('1') => msg += &t1,
+                Some('2') => msg += &t2,
                 _ => unreachable!(),
             },
             other => msg.push(other),
         }
     }
 
-    make_error!(location, "{}", msg)
+    make_error!(TypeMismatch; location, "{}", msg)
+}
+
+/// Returns the error from `from_template`, or None if either type is poisoned. An error has
+/// already been issued for a poisoned type, so a mismatch involving one would be redundant.
+pub fn from_types<'c>(
+    template: &str, location: Location<'c>, t1: &Type, t2: &Type, cache: &ModuleCache<'c>,
+) -> Option<ErrorMessage<'c>> {
+    if t1.is_poisoned(cache) || t2.is_poisoned(cache) {
+        None
+    } else {
+        Some(from_template(template, location, t1, t2, cache))
+    }
+}
+
+/// Issued when unifying would bind a type variable to a type containing itself, e.g. `a = a -> b`
+pub fn infinite_type<'c>(
+    location: Location<'c>, variable: TypeVariableId, in_type: &Type, cache: &ModuleCache<'c>,
+) -> ErrorMessage<'c> {
+    let variable = Type::TypeVariable(variable);
+    let types = show_types(&[in_type, &variable], cache);
+    make_error!(InfiniteType; location, "Cannot construct infinite type: {} occurs in {}", types[1], types[0])
+}
+
+/// Issued when accessing a field which a struct type is known not to have
+pub fn unknown_field<'c, 'f>(
+    location: Location<'c>, field: &str, typ: &Type, field_names: impl IntoIterator<Item = &'f str>,
+    cache: &ModuleCache<'c>,
+) -> ErrorMessage<'c> {
+    let typ = typ.display(cache);
+    match closest_match(field, field_names) {
+        Some(suggestion) => {
+            make_error!(NotFound; location, "Type `{}` has no field `{}`, did you mean `{}`?", typ, field, suggestion)
+        },
+        None => make_error!(NotFound; location, "Type `{}` has no field `{}`", typ, field),
+    }
+}
+
+/// Issued when a struct's constructor is called without a value for each of its fields
+pub fn missing_fields<'c>(
+    location: Location<'c>, typ: &Type, missing: &[String], cache: &ModuleCache<'c>,
+) -> ErrorMessage<'c> {
+    let plural_s = if missing.len() == 1 { "" } else { "s" };
+    let typ = typ.display(cache);
+    make_error!(WrongParameterCount; location, "Missing field{} in `{}`: {}", plural_s, typ, missing.join(", "))
 }
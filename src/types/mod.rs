@@ -115,6 +115,11 @@ pub enum Type {
     /// are included in it since they are still valid in a type position
     /// most notably when substituting type variables for effects.
     Effects(EffectSet),
+
+    /// The type of an expression which failed to type check. Errors unify with
+    /// every other type, which poisons any later uses of the expression so that
+    /// one type error doesn't cascade into many more error messages.
+    Error,
 }
 
 #[derive(Debug, Clone)]
@@ -200,9 +205,18 @@ impl Type {
             TypeVariable(_) => unreachable!("Constructors should always have concrete types"),
             Struct(_, _) => None,
             Effects(_) => None,
+            Error => None,
         }
     }
 
+    /// True if this type contains a Type::Error, in which case an error
+    /// has already been issued for the expression it belongs to.
+    pub fn is_poisoned(&self, cache: &ModuleCache) -> bool {
+        let mut poisoned = false;
+        self.traverse(cache, |typ| poisoned |= matches!(typ, Type::Error));
+        poisoned
+    }
+
     /// Pretty-print each type with each typevar substituted for a, b, c, etc.
     pub fn display<'a, 'b>(&self, cache: &'a ModuleCache<'b>) -> typeprinter::TypePrinter<'a, 'b> {
         let typ = GeneralizedType::MonoType(self.clone());
@@ -226,6 +240,7 @@ impl Type {
         match self {
             Type::Primitive(_) => (),
             Type::UserDefined(_) => (),
+            Type::Error => (),
 
             Type::Function(function) => {
                 for parameter in &function.parameters {
@@ -277,6 +292,7 @@ impl Type {
             Type::UserDefined(_) => (),
             Type::TypeVariable(_) => (),
             Type::Ref(_) => (),
+            Type::Error => (),
 
             Type::Function(function) => {
                 for parameter in &function.parameters {
@@ -343,6 +359,7 @@ impl Type {
                     format!("can {}, ..tv{}", effects.join(", "), set.replacement.0)
                 }
             },
+            Type::Error => "error".to_string(),
         }
     }
 }
@@ -160,7 +160,7 @@ fn is_mutually_recursive(pattern: &ast::Ast, cache: &ModuleCache) -> MutualRecur
             call.args.iter().fold(MutualRecursionResult::No, |a, b| a.combine(is_mutually_recursive(b, cache)))
         },
         _ => {
-            error!(pattern.locate(), "Invalid syntax in irrefutable pattern");
+            error!(InvalidPattern; pattern.locate(), "Invalid syntax in irrefutable pattern");
             MutualRecursionResult::No
         },
     }
@@ -31,13 +31,21 @@ pub fn compile<'c>(match_expr: &ast::Match<'c>, cache: &mut ModuleCache<'c>) ->
     if result.context.reachable_branches.len() != match_expr.branches.len() {
         for (i, (pattern, _branch)) in match_expr.branches.iter().enumerate() {
             if !result.context.reachable_branches.contains(&i) {
-                warning!(pattern.locate(), "Unreachable pattern");
+                let warning = make_warning!(pattern.locate(), "This pattern is unreachable");
+
+                // Only catch-all patterns are pointed out since it is otherwise
+                // possible for a pattern to be covered by several earlier ones.
+                let mut earlier_patterns = match_expr.branches[..i].iter().map(|(pattern, _)| pattern);
+                match earlier_patterns.find(|pattern| is_catch_all(pattern)) {
+                    Some(covering) => warning.with_label(covering.locate(), "matched earlier here").issue(),
+                    None => warning.issue(),
+                }
             }
         }
     }
 
     if result.context.missed_case_count != 0 {
-        result.issue_inexhaustive_errors(cache, match_expr.location);
+        result.issue_inexhaustive_error(cache, match_expr.location);
     }
 
     result.tree
@@ -61,6 +69,14 @@ pub enum VariantTag {
     Literal(ast::LiteralKind),
 }
 
+/// True if the given pattern is a lone variable, e.g. `x` or `_`, which matches any value
+fn is_catch_all(pattern: &Ast) -> bool {
+    match pattern {
+        Ast::Variable(variable) => !matches!(variable.kind, ast::VariableKind::TypeConstructor(_)),
+        _ => false,
+    }
+}
+
 /// Every pattern in a match expression is represented as a Constructor which
 /// itself is either a match-all pattern or a VariantTag followed by 0 or more
 /// sub-patterns to recurse onto.
@@ -217,12 +233,12 @@ impl PatternStack {
                     PatternStack(vec![(Variant(tag, fields), variable)])
                 },
                 _ => {
-                    error!(ast.locate(), "Invalid syntax used in pattern");
+                    error!(InvalidPattern; ast.locate(), "Invalid syntax used in pattern");
                     PatternStack(vec![])
                 },
             },
             _ => {
-                error!(ast.locate(), "Invalid syntax used in pattern");
+                error!(InvalidPattern; ast.locate(), "Invalid syntax used in pattern");
                 PatternStack(vec![])
             },
         }
@@ -618,16 +634,24 @@ impl DecisionTreeResult {
         DecisionTreeResult::new(DecisionTree::Leaf(branch), context)
     }
 
-    fn issue_inexhaustive_errors<'c>(&self, cache: &ModuleCache<'c>, location: Location<'c>) {
+    fn issue_inexhaustive_error<'c>(&self, cache: &ModuleCache<'c>, location: Location<'c>) {
         let mut bindings = BTreeMap::new();
-        DecisionTreeResult::issue_inexhaustive_errors_helper(&self.tree, None, &mut bindings, cache, location);
+        let mut missing_cases = vec![];
+        DecisionTreeResult::find_missing_cases(&self.tree, None, &mut bindings, &mut missing_cases, cache);
+
+        if missing_cases.is_empty() {
+            error!(NonExhaustive; location, "Match is not exhaustive; some patterns are not covered");
+        } else {
+            let missing_cases = join_with(&missing_cases, ", ");
+            error!(NonExhaustive; location, "Match is not exhaustive; missing cases: {}", missing_cases);
+        }
     }
 
     /// Recurses the DecisionTree, searching for Fail nodes and reconstructing the data as it goes.
     /// When this hits a Fail node, the reconstructed piece of data will be a missing case.
-    fn issue_inexhaustive_errors_helper<'c>(
+    fn find_missing_cases(
         tree: &DecisionTree, starting_id: Option<DefinitionInfoId>, bindings: &mut DebugMatchBindings,
-        cache: &ModuleCache<'c>, location: Location<'c>,
+        missing_cases: &mut Vec<String>, cache: &ModuleCache,
     ) {
         use DecisionTree::*;
         match tree {
@@ -642,18 +666,21 @@ impl DecisionTreeResult {
 
                             for tag in get_missing_cases(&covered_cases, cache) {
                                 bindings.insert(*id, DebugConstructor::new(&Some(tag), cache));
-                                DecisionTreeResult::issue_inexhaustive_error(starting_id, bindings, location);
+                                let case = starting_id.map_or("_".to_string(), |id| {
+                                    DecisionTreeResult::construct_missing_case_string(id, bindings)
+                                });
+                                missing_cases.push(case);
                             }
                         },
                         _ => {
                             bindings.insert(*id, DebugConstructor::from_case(case, cache));
                             let starting_id = starting_id.or(Some(*id));
-                            DecisionTreeResult::issue_inexhaustive_errors_helper(
+                            DecisionTreeResult::find_missing_cases(
                                 &case.branch,
                                 starting_id,
                                 bindings,
+                                missing_cases,
                                 cache,
-                                location,
                             );
                         },
                     }
@@ -663,15 +690,6 @@ impl DecisionTreeResult {
         }
     }
 
-    fn issue_inexhaustive_error(
-        starting_id: Option<DefinitionInfoId>, bindings: &DebugMatchBindings, location: Location,
-    ) {
-        let case =
-            starting_id.map_or("_".to_string(), |id| DecisionTreeResult::construct_missing_case_string(id, bindings));
-
-        error!(location, "Missing case {}", case);
-    }
-
     /// Construct the string representation of the data defined by the starting DefinitionInfoId
     /// and given DebugMatchBindings. This is recursive since the id may refer to a DebugConstructor
     /// which itself has more DefinitionInfoId fields that need to be converted to Strings.
@@ -777,8 +795,8 @@ impl DebugConstructor {
         use VariantTag::*;
         let tag = match &tag {
             Some(UserDefined(id)) => cache.definition_infos[id.0].name.clone(),
-            Some(Literal(LiteralKind::Integer(_, Some(kind)))) => format!("_ : {}", kind),
-            Some(Literal(LiteralKind::Integer(_, None))) => format!("_ : Int"),
+            Some(Literal(LiteralKind::Integer(_, Some(kind), _))) => format!("_ : {}", kind),
+            Some(Literal(LiteralKind::Integer(_, None, _))) => format!("_ : Int"),
             Some(Literal(LiteralKind::Float(_, Some(kind)))) => format!("_ : {}", kind),
             Some(Literal(LiteralKind::Float(_, None))) => format!("_ : Float"),
             Some(Literal(LiteralKind::String(_))) => "_ : string".to_string(),
@@ -921,8 +939,8 @@ impl Case {
                 let constructor_type = unwrap_clone(&cache.definition_infos[id.0].typ);
                 constructor_type.instantiate(vec![], cache).0
             },
-            Some(Literal(LiteralKind::Integer(_, Some(kind)))) => Type::int(*kind),
-            Some(Literal(LiteralKind::Integer(_, None))) => Type::polymorphic_int(typechecker::next_type_variable_id(cache)),
+            Some(Literal(LiteralKind::Integer(_, Some(kind), _))) => Type::int(*kind),
+            Some(Literal(LiteralKind::Integer(_, None, _))) => Type::polymorphic_int(typechecker::next_type_variable_id(cache)),
             Some(Literal(LiteralKind::Float(_, Some(kind)))) => Type::float(*kind),
             Some(Literal(LiteralKind::Float(_, None))) => Type::polymorphic_float(typechecker::next_type_variable_id(cache)),
             Some(Literal(LiteralKind::String(_))) => Type::UserDefined(STRING_TYPE),
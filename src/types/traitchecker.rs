@@ -19,6 +19,8 @@
 //! a compile-time error will be issued.
 use std::sync::atomic::AtomicBool;
 
+use colored::Colorize;
+
 use crate::cache::{ImplInfoId, ModuleCache};
 use crate::lexer::token::{IntegerKind, FloatKind};
 use crate::types::traits::{RequiredTrait, TraitConstraint, TraitConstraints};
@@ -197,6 +199,11 @@ fn try_solve_normal_constraint<'a, 'c>(
 /// Search and bind a specific impl to the given TraitConstraint, erroring if 0
 /// or >1 matching impls are found.
 fn solve_normal_constraint<'c>(constraint: &TraitConstraint, cache: &mut ModuleCache<'c>) {
+    // A poisoned type would match every impl, and an error has already been issued for it anyway
+    if constraint.args().iter().any(|arg| arg.is_poisoned(cache)) {
+        return;
+    }
+
     let bindings = UnificationBindings::empty();
     let mut matching_impls = find_matching_impls(constraint, &bindings, RECURSION_LIMIT, cache);
 
@@ -208,8 +215,8 @@ fn solve_normal_constraint<'c>(constraint: &TraitConstraint, cache: &mut ModuleC
             bind_impl(impl_id, constraint, cache);
         }
     } else if matching_impls.len() > 1 {
-        error!(
-            constraint.locate(cache),
+        let mut error = make_error!(
+            MultipleMatchingImpls; constraint.locate(cache),
             "{} matching impls found for {}",
             matching_impls.len(),
             constraint.display(cache)
@@ -217,16 +224,35 @@ fn solve_normal_constraint<'c>(constraint: &TraitConstraint, cache: &mut ModuleC
 
         let max_shown_impls = 3;
         for (i, (impls, _)) in matching_impls.iter().enumerate().take(max_shown_impls) {
-            let impl_id = impls[0].0;
+            let location = cache[impls[0].0].location;
             if i == 2 && matching_impls.len() > max_shown_impls {
                 let rest = matching_impls.len() - max_shown_impls;
-                note!(cache[impl_id].location, "Candidate {} ({} more hidden)", i + 1, rest);
+                error = error.with_label(location, format!("candidate here ({} more hidden)", rest));
             } else {
-                note!(cache[impl_id].location, "Candidate {}", i + 1);
+                error = error.with_label(location, "candidate here");
             }
         }
+        error.issue();
+
+        // If the constraint still contains type variables, more than one impl may only match
+        // because the types involved weren't known. An annotation would fix this.
+        let is_ambiguous =
+            constraint.args().iter().any(|arg| !typechecker::find_all_typevars(arg, false, cache).is_empty());
+
+        if is_ambiguous {
+            note!(constraint.locate(cache), "consider adding a type annotation");
+        }
     } else {
-        error!(constraint.locate(cache), "No impl found for {}", constraint.display(cache))
+        // Single-parameter traits on a known type read better phrased in terms of the type,
+        // but with a type variable it's the missing impl itself that's most relevant
+        match constraint.args() {
+            [typ] if typechecker::find_all_typevars(typ, false, cache).is_empty() => {
+                let trait_name = cache[constraint.trait_id()].name.blue();
+                let typ = typ.display(cache);
+                error!(NoMatchingImpl; constraint.locate(cache), "Type {} does not implement {}", typ, trait_name)
+            },
+            _ => error!(NoMatchingImpl; constraint.locate(cache), "No impl found for {}", constraint.display(cache)),
+        }
     }
 }
 
@@ -28,8 +28,8 @@
 //! - `decision_tree: Option<DecisionTree>` for `ast::Match`s
 use crate::cache::{DefinitionInfoId, DefinitionKind, EffectInfoId, ModuleCache, TraitInfoId};
 use crate::cache::{ImplScopeId, VariableId};
-use crate::error::location::{Locatable, Location};
-use crate::error::{get_error_count, ErrorMessage};
+use crate::error::location::{EndPosition, Locatable, Location};
+use crate::error::ErrorMessage;
 use crate::parser::ast::{self, ClosureEnvironment};
 use crate::types::traits::{RequiredTrait, TraitConstraint, TraitConstraints};
 use crate::types::typed::Typed;
@@ -62,8 +62,21 @@ pub type TypeBindings = HashMap<TypeVariableId, Type>;
 /// or an error message of which types failed to unify.
 pub type UnificationResult<'c> = Result<UnificationBindings, ErrorMessage<'c>>;
 
+/// The reason two types failed to unify within `try_unify_with_bindings_inner`
+#[derive(Debug)]
+pub enum UnificationError {
+    /// The types are incompatible, reported using the caller's error message template
+    Mismatch,
+
+    /// Binding the type variable to the type would create an infinite type, e.g. `a = a -> b`
+    InfiniteType(TypeVariableId, Type),
+}
+
 type LevelBindings = Vec<(TypeVariableId, LetBindingLevel)>;
 
+/// Label attached to type errors pointing at the type annotation that required the expected type
+const EXPECTED_BECAUSE_OF: &str = "expected type comes from here";
+
 /// Arbitrary limit of maximum recursive calls to functions like find_binding.
 /// Expected not to happen but leads to better errors than a stack overflow when it does.
 const RECURSION_LIMIT: u32 = 15;
@@ -202,6 +215,7 @@ pub fn replace_all_typevars_with_bindings<'c>(
             Function(FunctionType { parameters, return_type, environment, is_varargs, effects })
         },
         UserDefined(id) => UserDefined(*id),
+        Error => Error,
 
         // We must recurse on the lifetime variable since they are unified as normal type variables
         Ref(lifetime) => match replace_typevar_with_binding(*lifetime, new_bindings, Ref, cache) {
@@ -274,6 +288,7 @@ pub fn bind_typevars<'c>(typ: &Type, type_bindings: &TypeBindings, cache: &Modul
             Function(FunctionType { parameters, return_type, environment, is_varargs, effects })
         },
         UserDefined(id) => UserDefined(*id),
+        Error => Error,
 
         Ref(lifetime) => match bind_typevar(*lifetime, type_bindings, Ref, cache) {
             TypeVariable(new_lifetime) => Ref(new_lifetime),
@@ -346,6 +361,7 @@ pub fn contains_any_typevars_from_list<'c>(typ: &Type, list: &[TypeVariableId],
     match typ {
         Primitive(_) => false,
         UserDefined(_) => false,
+        Error => false,
 
         TypeVariable(id) => type_variable_contains_any_typevars_from_list(*id, list, cache),
 
@@ -547,6 +563,7 @@ pub(super) fn occurs<'b>(
     match typ {
         Primitive(_) => OccursResult::does_not_occur(),
         UserDefined(_) => OccursResult::does_not_occur(),
+        Error => OccursResult::does_not_occur(),
 
         TypeVariable(var_id) => typevars_match(id, level, *var_id, bindings, fuel, cache),
         Function(function) => occurs(id, level, &function.return_type, bindings, fuel, cache)
@@ -614,7 +631,7 @@ pub fn follow_bindings_in_cache<'b>(typ: &Type, cache: &ModuleCache<'b>) -> Type
 #[allow(clippy::nonminimal_bool)]
 pub fn try_unify_with_bindings_inner<'b>(
     t1: &Type, t2: &Type, bindings: &mut UnificationBindings, location: Location<'b>, cache: &mut ModuleCache<'b>,
-) -> Result<(), ()> {
+) -> Result<(), UnificationError> {
     match (t1, t2) {
         (Primitive(p1), Primitive(p2)) if p1 == p2 => Ok(()),
 
@@ -630,6 +647,9 @@ pub fn try_unify_with_bindings_inner<'b>(
 
         (_, TypeVariable(id)) => try_unify_type_variable_with_bindings(*id, t2, t1, bindings, location, cache),
 
+        // An error has already been issued for any poisoned type, don't issue another
+        (Error, _) | (_, Error) => Ok(()),
+
         (Function(function1), Function(function2)) => {
             if function1.parameters.len() != function2.parameters.len() {
                 // Whether a function is varargs or not is never unified,
@@ -637,7 +657,7 @@ pub fn try_unify_with_bindings_inner<'b>(
                 if !(function1.is_varargs && function2.parameters.len() >= function1.parameters.len())
                     && !(function2.is_varargs && function1.parameters.len() >= function2.parameters.len())
                 {
-                    return Err(());
+                    return Err(UnificationError::Mismatch);
                 }
             }
 
@@ -655,7 +675,7 @@ pub fn try_unify_with_bindings_inner<'b>(
             try_unify_with_bindings_inner(a_constructor, b_constructor, bindings, location, cache)?;
 
             if a_args.len() != b_args.len() {
-                return Err(());
+                return Err(UnificationError::Mismatch);
             }
 
             for (a_arg, b_arg) in a_args.iter().zip(b_args.iter()) {
@@ -695,14 +715,14 @@ pub fn try_unify_with_bindings_inner<'b>(
             Ok(())
         },
 
-        _ => Err(()),
+        _ => Err(UnificationError::Mismatch),
     }
 }
 
 fn bind_struct_fields<'c>(
     fields1: &BTreeMap<String, Type>, fields2: &BTreeMap<String, Type>, rest1: TypeVariableId, rest2: TypeVariableId,
     bindings: &mut UnificationBindings, location: Location<'c>, cache: &mut ModuleCache<'c>,
-) -> Result<(), ()> {
+) -> Result<(), UnificationError> {
     let mut new_fields = fields1.clone();
     for (name, typ2) in fields2 {
         if let Some(typ1) = new_fields.get(name) {
@@ -755,7 +775,7 @@ fn new_row_variable(row1: TypeVariableId, row2: TypeVariableId, cache: &mut Modu
 fn bind_struct_fields_subset<'c>(
     fields: &BTreeMap<String, Type>, template: &BTreeMap<String, Type>, bindings: &mut UnificationBindings,
     location: Location<'c>, cache: &mut ModuleCache<'c>,
-) -> Result<(), ()> {
+) -> Result<(), UnificationError> {
     // FIXME: Enforcing a struct type's fields are a subset of
     // a data type's fields works for cases like
     // ```
@@ -772,7 +792,7 @@ fn bind_struct_fields_subset<'c>(
     // ```
     // Since the struct has a subset of T's fields this would currently pass.
     if fields.len() > template.len() {
-        return Err(());
+        return Err(UnificationError::Mismatch);
     }
 
     for (name, field) in fields {
@@ -780,7 +800,7 @@ fn bind_struct_fields_subset<'c>(
             Some(template_field) => {
                 try_unify_with_bindings_inner(template_field, field, bindings, location, cache)?;
             },
-            None => return Err(()),
+            None => return Err(UnificationError::Mismatch),
         }
     }
 
@@ -789,13 +809,13 @@ fn bind_struct_fields_subset<'c>(
 
 fn get_fields<'c>(
     typ: &Type, args: &[Type], bindings: &mut UnificationBindings, cache: &mut ModuleCache<'c>,
-) -> Result<BTreeMap<String, Type>, ()> {
+) -> Result<BTreeMap<String, Type>, UnificationError> {
     match typ {
         UserDefined(id) => {
             let info = &cache[*id];
             match &info.body {
                 TypeInfoBody::Alias(typ) => get_fields(&typ.clone(), args, bindings, cache),
-                TypeInfoBody::Union(_) => Err(()),
+                TypeInfoBody::Union(_) => Err(UnificationError::Mismatch),
                 TypeInfoBody::Unknown => unreachable!(),
                 TypeInfoBody::Struct(fields) => {
                     let mut more_bindings = HashMap::new();
@@ -827,9 +847,9 @@ fn get_fields<'c>(
         },
         TypeVariable(id) => match &cache.type_bindings[id.0] {
             Bound(binding) => get_fields(&binding.clone(), args, bindings, cache),
-            Unbound(_, _) => Err(()),
+            Unbound(_, _) => Err(UnificationError::Mismatch),
         },
-        _ => Err(()),
+        _ => Err(UnificationError::Mismatch),
     }
 }
 
@@ -838,7 +858,7 @@ fn get_fields<'c>(
 fn try_unify_type_variable_with_bindings<'c>(
     id: TypeVariableId, a: &Type, b: &Type, bindings: &mut UnificationBindings, location: Location<'c>,
     cache: &mut ModuleCache<'c>,
-) -> Result<(), ()> {
+) -> Result<(), UnificationError> {
     match find_binding(id, bindings, cache) {
         Bound(a) => try_unify_with_bindings_inner(&a, b, bindings, location, cache),
         Unbound(a_level, _a_kind) => {
@@ -848,8 +868,7 @@ fn try_unify_type_variable_with_bindings<'c>(
             if *a != b {
                 let result = occurs(id, a_level, &b, bindings, RECURSION_LIMIT, cache);
                 if result.occurs {
-                    // TODO: Need better error messages for recursive types
-                    Err(())
+                    Err(UnificationError::InfiniteType(id, b))
                 } else {
                     bindings.bindings.insert(id, b);
                     Ok(())
@@ -867,7 +886,11 @@ pub fn try_unify_with_bindings<'b>(
 ) -> Result<(), ErrorMessage<'b>> {
     match try_unify_with_bindings_inner(t1, t2, bindings, location, cache) {
         Ok(()) => Ok(()),
-        Err(()) => Err(error::from_template(error_message, location, t1, t2, cache)),
+        Err(UnificationError::Mismatch) => match error::from_types(error_message, location, t1, t2, cache) {
+            Some(error) => Err(error),
+            None => Ok(()),
+        },
+        Err(UnificationError::InfiniteType(id, typ)) => Err(error::infinite_type(location, id, &typ, cache)),
     }
 }
 
@@ -892,7 +915,7 @@ pub fn try_unify_all_with_bindings<'c>(
         // try_unify_with_bindings! We'd need access to the full type to give better
         // errors like the other function does.
         return Err(make_error!(
-            location,
+            TypeMismatch; location,
             "Type-length mismatch: {} versus {} when unifying [{}] and [{}]",
             vec1.len(),
             vec2.len(),
@@ -914,18 +937,28 @@ fn concat_type_strings<'c>(types: &[Type], cache: &ModuleCache<'c>) -> String {
 }
 
 /// Unifies the two given types, remembering the unification results in the cache.
-/// If this operation fails, a user-facing error message is emitted.
-pub fn unify<'c>(t1: &Type, t2: &Type, location: Location<'c>, cache: &mut ModuleCache<'c>, error_message: &str) {
-    perform_bindings_or_print_error(try_unify(t1, t2, location, cache, error_message), cache);
+/// If this operation fails, a user-facing error message is emitted and false is returned.
+pub fn unify<'c>(
+    t1: &Type, t2: &Type, location: Location<'c>, cache: &mut ModuleCache<'c>, error_message: &str,
+) -> bool {
+    perform_bindings_or_print_error(try_unify(t1, t2, location, cache, error_message), cache)
 }
 
 /// Helper for committing to the results of try_unify.
 /// Places all the typevar bindings in the cache to be remembered,
-/// or otherwise prints out the given error message.
-pub fn perform_bindings_or_print_error<'c>(unification_result: UnificationResult<'c>, cache: &mut ModuleCache<'c>) {
+/// or otherwise prints out the given error message and returns false.
+pub fn perform_bindings_or_print_error<'c>(
+    unification_result: UnificationResult<'c>, cache: &mut ModuleCache<'c>,
+) -> bool {
     match unification_result {
-        Ok(bindings) => bindings.perform(cache),
-        Err(message) => eprintln!("{}", message),
+        Ok(bindings) => {
+            bindings.perform(cache);
+            true
+        },
+        Err(message) => {
+            message.issue();
+            false
+        },
     }
 }
 
@@ -951,6 +984,7 @@ pub fn find_all_typevars<'a>(typ: &Type, polymorphic_only: bool, cache: &ModuleC
     match typ {
         Primitive(_) => vec![],
         UserDefined(_) => vec![],
+        Error => vec![],
         TypeVariable(id) => find_typevars_in_typevar_binding(*id, polymorphic_only, cache),
         Function(function) => {
             let mut type_variables = vec![];
@@ -1165,7 +1199,7 @@ pub(super) fn bind_irrefutable_pattern<'c>(
                 let msg = "Expected a unit type from this pattern, but the corresponding value has the type $1";
                 unify(typ, &Type::UNIT, ast.locate(), cache, msg);
             },
-            _ => error!(ast.locate(), "Pattern is not irrefutable"),
+            _ => error!(InvalidPattern; ast.locate(), "Pattern is not irrefutable"),
         },
         Variable(variable) => {
             let definition_id = variable.definition.unwrap();
@@ -1195,7 +1229,9 @@ pub(super) fn bind_irrefutable_pattern<'c>(
         },
         TypeAnnotation(annotation) => {
             let msg = "Pattern type $1 does not match the annotated type $2";
-            unify(typ, annotation.typ.as_ref().unwrap(), annotation.location, cache, msg);
+            let result = try_unify(typ, annotation.typ.as_ref().unwrap(), annotation.location, cache, msg);
+            let result = result.map_err(|error| error.with_label(annotation.rhs.locate(), EXPECTED_BECAUSE_OF));
+            perform_bindings_or_print_error(result, cache);
             bind_irrefutable_pattern(annotation.lhs.as_mut(), typ, required_traits, should_generalize, cache);
         },
         // TODO: All struct patterns
@@ -1227,7 +1263,7 @@ pub(super) fn bind_irrefutable_pattern<'c>(
             }
         },
         _ => {
-            error!(ast.locate(), "Invalid syntax in irrefutable pattern");
+            error!(InvalidPattern; ast.locate(), "Invalid syntax in irrefutable pattern");
         },
     }
 }
@@ -1302,7 +1338,7 @@ pub(super) fn foreach_variable(pattern: &ast::Ast, f: &mut impl FnMut(&ast::Vari
             }
         },
         _ => {
-            error!(pattern.locate(), "Invalid syntax in irrefutable pattern in trait impl, expected a pattern of some kind (a name, type annotation, or type constructor)");
+            error!(InvalidPattern; pattern.locate(), "Invalid syntax in irrefutable pattern in trait impl, expected a pattern of some kind (a name, type annotation, or type constructor)");
         },
     }
 }
@@ -1469,8 +1505,12 @@ impl<'a> Inferable<'a> for ast::Literal<'a> {
     fn infer_impl(&mut self, cache: &mut ModuleCache<'a>) -> TypeResult {
         use ast::LiteralKind::*;
         match self.kind {
-            Integer(_, kind) => {
+            Integer(value, kind, negative) => {
                 let t = if let Some(kind) = kind {
+                    if !kind.can_hold_literal(value, negative) {
+                        let literal = if negative { (value as i64).to_string() } else { value.to_string() };
+                        error!(IntegerOverflow; self.location, "Literal {} does not fit in type {}", literal, kind);
+                    }
                     Type::int(kind)
                 } else {
                     Type::polymorphic_int(next_type_variable_id(cache))
@@ -1564,7 +1604,16 @@ impl<'a> Inferable<'a> for ast::Lambda<'a> {
             let typ = typ.clone();
             let body = self.body.infer_impl(cache);
             let msg = "Function body type $1 does not match declared return type of $2";
-            unify(&typ, &body.typ, self.location, cache, msg);
+            match try_unify(&body.typ, &typ, self.location, cache, msg) {
+                Ok(bindings) => bindings.perform(cache),
+                // A body of type Unit most likely means the function ends without returning a value,
+                // e.g. with an `if` lacking an `else`, rather than returning a value of the wrong type.
+                Err(_) if follow_bindings_in_cache(&body.typ, cache) == Type::UNIT => {
+                    let typ = typ.display(cache);
+                    error!(TypeMismatch; self.location, "Function must return a value of type {}", typ);
+                },
+                Err(error) => error.issue(),
+            }
             body
         } else {
             infer(self.body.as_mut(), cache)
@@ -1613,7 +1662,15 @@ impl<'a> Inferable<'a> for ast::FunctionCall<'a> {
         match try_unify(&f.typ, &new_function, self.location, cache, "Value being called is not a function, it is a $1")
         {
             Ok(bindings) => bindings.perform(cache),
-            Err(error) => issue_argument_types_error(self, f.typ.clone(), new_function, error, cache),
+            Err(error) => {
+                let error_count = cache.error_count();
+                issue_argument_types_error(self, f.typ.clone(), new_function, error, cache);
+
+                // Poison the result so uses of it don't issue further errors
+                if cache.error_count() != error_count {
+                    return f.with_type(Type::Error);
+                }
+            },
         }
 
         f.with_type(return_type)
@@ -1626,20 +1683,81 @@ fn issue_argument_types_error<'c>(
     match try_unwrap_functions(f, args, cache) {
         Some((expected, actual)) => {
             if expected.parameters.len() != actual.parameters.len() && !expected.is_varargs && !actual.is_varargs {
-                error!(
-                    call.location,
-                    "Function {} declared to take {} parameter(s), but {} were supplied",
-                    Function(expected.clone()).display(cache),
-                    expected.parameters.len(),
-                    actual.parameters.len()
-                )
+                match missing_struct_fields(call, &expected, actual.parameters.len(), cache) {
+                    Some(missing) => {
+                        error::missing_fields(call.location, &expected.return_type, &missing, cache).issue()
+                    },
+                    None => error!(
+                        WrongParameterCount; call.location,
+                        "Function {} declared to take {} parameter(s), but {} were supplied",
+                        Function(expected.clone()).display(cache),
+                        expected.parameters.len(),
+                        actual.parameters.len()
+                    ),
+                }
             }
 
-            for ((arg, param), arg_ast) in actual.parameters.into_iter().zip(expected.parameters).zip(&call.args) {
-                unify(&arg, &param, arg_ast.locate(), cache, "Expected argument of type $2, but found $1");
+            let arguments = actual.parameters.into_iter().zip(expected.parameters).zip(&call.args);
+            for (i, ((arg, param), arg_ast)) in arguments.enumerate() {
+                let msg = "Expected argument of type $2, but found $1";
+                match try_unify(&arg, &param, arg_ast.locate(), cache, msg) {
+                    Ok(bindings) => bindings.perform(cache),
+                    Err(error) => match parameter_location(&call.function, i, cache) {
+                        Some(location) => error.with_label(location, "parameter declared here").issue(),
+                        None => error.issue(),
+                    },
+                }
             }
         },
-        None => eprintln!("{}", original_error),
+        None => original_error.issue(),
+    }
+}
+
+/// If `call` is to the constructor of a struct type and is given too few arguments,
+/// returns the names of the fields which were not given a value.
+fn missing_struct_fields<'c>(
+    call: &ast::FunctionCall<'c>, expected: &FunctionType, argument_count: usize, cache: &ModuleCache<'c>,
+) -> Option<Vec<String>> {
+    let id = match call.function.as_ref() {
+        ast::Ast::Variable(variable) => variable.definition?,
+        _ => return None,
+    };
+
+    if !matches!(cache[id].definition, Some(DefinitionKind::TypeConstructor { .. })) {
+        return None;
+    }
+
+    let type_id = match follow_bindings_in_cache(&expected.return_type, cache) {
+        UserDefined(id) => id,
+        TypeApplication(constructor, _) => match follow_bindings_in_cache(&constructor, cache) {
+            UserDefined(id) => id,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    match &cache[type_id].body {
+        TypeInfoBody::Struct(fields) if argument_count < fields.len() => {
+            Some(fields[argument_count..].iter().map(|field| field.name.clone()).collect())
+        },
+        _ => None,
+    }
+}
+
+/// Returns the location of the given parameter if the function being called
+/// refers directly to a function definition.
+fn parameter_location<'c>(function: &ast::Ast<'c>, index: usize, cache: &ModuleCache<'c>) -> Option<Location<'c>> {
+    let id = match function {
+        ast::Ast::Variable(variable) => variable.definition?,
+        _ => return None,
+    };
+
+    match &cache[id].definition {
+        Some(DefinitionKind::Definition(definition)) => match definition.expr.as_ref() {
+            ast::Ast::Lambda(lambda) => lambda.args.get(index).map(|arg| arg.locate()),
+            _ => None,
+        },
+        _ => None,
     }
 }
 
@@ -1730,14 +1848,17 @@ impl<'a> Inferable<'a> for ast::If<'a> {
         result.combine(&mut otherwise, cache);
 
         let msg = "Expected 'then' and 'else' branch types to match, but found $1 and $2 respectively";
-        unify(&then.typ, &otherwise.typ, self.location, cache, msg);
-        result.with_type(then.typ)
+        if unify(&then.typ, &otherwise.typ, self.location, cache, msg) {
+            result.with_type(then.typ)
+        } else {
+            result.with_type(Type::Error)
+        }
     }
 }
 
 impl<'a> Inferable<'a> for ast::Match<'a> {
     fn infer_impl(&mut self, cache: &mut ModuleCache<'a>) -> TypeResult {
-        let error_count = get_error_count();
+        let error_count = cache.error_count();
 
         let mut result = infer(self.expression.as_mut(), cache);
         let mut return_type = next_type_variable(cache);
@@ -1763,7 +1884,9 @@ impl<'a> Inferable<'a> for ast::Match<'a> {
                 unify(&result.typ, &pattern_result.typ, pattern.locate(), cache, msg);
 
                 let msg = "This branch's return type $2 does not match the previous branches which return $1";
-                unify(&return_type, &branch_result.typ, branch.locate(), cache, msg);
+                if !unify(&return_type, &branch_result.typ, branch.locate(), cache, msg) {
+                    return_type = Type::Error;
+                }
 
                 result.combine(&mut pattern_result, cache);
                 result.combine(&mut branch_result, cache);
@@ -1772,7 +1895,7 @@ impl<'a> Inferable<'a> for ast::Match<'a> {
 
         // Compiling the decision tree for this pattern requires each pattern is well-typed.
         // So skip this step if there was an error in inferring types for this match expression.
-        if get_error_count() == error_count {
+        if cache.error_count() == error_count {
             let mut tree = pattern::compile(self, cache);
             // TODO: Infer new variables created by a decision tree within pattern::compile.
             //       It is done separately currently only for convenience/ease of implementation.
@@ -1795,7 +1918,9 @@ impl<'a> Inferable<'a> for ast::TypeAnnotation<'a> {
         let lhs = infer(self.lhs.as_mut(), cache);
 
         let msg = "Expression of type $1 does not match its annotated type $2";
-        unify(&lhs.typ, self.typ.as_mut().unwrap(), self.location, cache, msg);
+        let result = try_unify(&lhs.typ, self.typ.as_mut().unwrap(), self.lhs.locate(), cache, msg);
+        let result = result.map_err(|error| error.with_label(self.rhs.locate(), EXPECTED_BECAUSE_OF));
+        perform_bindings_or_print_error(result, cache);
         lhs
     }
 }
@@ -1940,7 +2065,21 @@ impl<'a> Inferable<'a> for ast::MemberAccess<'a> {
         let struct_type = Type::Struct(fields, rho);
 
         let msg = &format!("$1 has no field {} of type $2", self.field);
-        unify(&result.typ, &struct_type, self.location, cache, msg);
+        match try_unify(&result.typ, &struct_type, self.location, cache, msg) {
+            Ok(bindings) => bindings.perform(cache),
+            Err(message) => {
+                // Prefer a more specific error if the type is known and the field is simply missing
+                let fields = get_fields(&result.typ, &[], &mut UnificationBindings::empty(), cache);
+                match fields {
+                    Ok(fields) if !fields.contains_key(&self.field) => {
+                        let field_names = fields.keys().map(|name| name.as_str());
+                        let error = error::unknown_field(self.location, &self.field, &result.typ, field_names, cache);
+                        error.issue();
+                    },
+                    _ => message.issue(),
+                }
+            },
+        }
 
         if self.is_offset {
             field_type = ref_of(field_type, cache);
@@ -1961,7 +2100,7 @@ impl<'a> Inferable<'a> for ast::Assignment<'a> {
 
         match try_unify(&result.typ, &mutref, self.location, cache, "never shown") {
             Ok(bindings) => bindings.perform(cache),
-            Err(_) => issue_assignment_error(&result.typ, self.lhs.locate(), &rhs.typ, self.location, cache),
+            Err(_) => issue_assignment_error(&result.typ, &self.lhs, &rhs.typ, self.location, cache),
         }
 
         result.with_type(Type::UNIT)
@@ -1969,16 +2108,20 @@ impl<'a> Inferable<'a> for ast::Assignment<'a> {
 }
 
 fn issue_assignment_error<'c>(
-    lhs: &Type, lhs_loc: Location<'c>, rhs: &Type, location: Location<'c>, cache: &mut ModuleCache<'c>,
+    lhs: &Type, lhs_ast: &ast::Ast<'c>, rhs: &Type, location: Location<'c>, cache: &mut ModuleCache<'c>,
 ) {
     // Try to offer a more specific error message
     let lifetime = next_type_variable_id(cache);
     let var = next_type_variable(cache);
     let mutref = Type::TypeApplication(Box::new(Type::Ref(lifetime)), vec![var]);
 
+    let lhs_loc = lhs_ast.locate();
     let msg = "Expression of type $1 must be a `ref a` type to be assigned to";
     if let Err(msg) = try_unify(lhs, &mutref, lhs_loc, cache, msg) {
-        eprintln!("{}", msg);
+        match immutable_binding_error(lhs_ast, cache) {
+            Some(error) => error.issue(),
+            None => msg.issue(),
+        }
     } else {
         let inner_type = match follow_bindings_in_cache(lhs, cache) {
             TypeApplication(_, mut args) => args.remove(0),
@@ -1987,10 +2130,38 @@ fn issue_assignment_error<'c>(
 
         let msg = "Cannot assign expression of type $2 to a ref of type $1";
         let msg = try_unify(&inner_type, rhs, location, cache, msg).unwrap_err();
-        eprintln!("{}", msg);
+        msg.issue();
     }
 }
 
+/// If `lhs` names a variable defined without `mut`, returns an error for assigning to it
+/// suggesting to declare it mutable instead.
+fn immutable_binding_error<'c>(lhs: &ast::Ast<'c>, cache: &ModuleCache<'c>) -> Option<ErrorMessage<'c>> {
+    let (name, id) = match lhs {
+        ast::Ast::Variable(ast::Variable { kind: ast::VariableKind::Identifier(name), definition, .. }) => {
+            (name, (*definition)?)
+        },
+        _ => return None,
+    };
+
+    let definition = match &cache[id].definition {
+        Some(DefinitionKind::Definition(definition)) if !definition.mutable => definition,
+        _ => return None,
+    };
+
+    // Destructured variables can't be made mutable individually, so they keep the general error
+    if !matches!(definition.pattern.as_ref(), ast::Ast::Variable(_)) {
+        return None;
+    }
+
+    // Suggest inserting `mut ` just before the value the variable is defined as
+    let start = definition.expr.locate();
+    let insert_location = Location::new(start.filename, start.start, EndPosition::new(start.start.index));
+
+    let error = make_error!(AssignToImmutable; lhs.locate(), "Cannot assign to immutable binding `{}`", name);
+    Some(error.with_suggestion("declare it mutable", insert_location, "mut "))
+}
+
 impl<'a> Inferable<'a> for ast::EffectDefinition<'a> {
     fn infer_impl(&mut self, cache: &mut ModuleCache<'a>) -> TypeResult {
         let previous_level = CURRENT_LEVEL.swap(self.level.unwrap().0, Ordering::SeqCst);
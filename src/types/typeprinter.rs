@@ -4,10 +4,13 @@
 //! types/traits are displayed via `type.display(cache)` rather than directly having
 //! a Display impl.
 use crate::cache::{ModuleCache, TraitInfoId};
+use crate::error::{self, strip_ansi};
 use crate::types::traits::{ConstraintSignature, ConstraintSignaturePrinter, RequiredTrait, TraitConstraintId};
 use crate::types::typechecker::find_all_typevars;
 use crate::types::{FunctionType, PrimitiveType, Type, TypeBinding, TypeInfoId, TypeVariableId};
+use crate::util::fmap;
 
+use std::cell::Cell;
 use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Debug, Display, Formatter};
@@ -27,6 +30,10 @@ pub struct TypePrinter<'a, 'b> {
     /// Controls whether to show or hide some hidden data, like ref lifetimes
     debug: bool,
 
+    /// The number of types the type currently being printed is nested within,
+    /// used to abbreviate types nested deeper than `error::max_type_depth`
+    depth: Cell<usize>,
+
     cache: &'a ModuleCache<'b>,
 }
 
@@ -72,7 +79,7 @@ pub fn show_type_and_traits<'b>(
 
     let debug = true;
     let typ = typ.clone();
-    let type_string = TypePrinter { typ, cache, debug, typevar_names: map.clone() }.to_string();
+    let type_string = TypePrinter { typ, cache, debug, typevar_names: map.clone(), depth: Cell::new(0) }.to_string();
 
     let mut traits = traits
         .iter()
@@ -110,11 +117,49 @@ pub fn show_type_and_traits<'b>(
     (type_string, traits)
 }
 
+/// Returns a string of each of the given types. As with `show_type_and_traits`, type variables
+/// are named consistently across all the types rather than restarting at `a` for each one.
+pub fn show_types<'b>(types: &[&Type], cache: &ModuleCache<'b>) -> Vec<String> {
+    let mut map = HashMap::new();
+    let mut current = 'a';
+
+    fmap(types, |typ| {
+        fill_typevar_map(&mut map, find_all_typevars(typ, false, cache), &mut current);
+        let typ = GeneralizedType::MonoType((*typ).clone());
+        TypePrinter { typ, cache, debug: false, typevar_names: map.clone(), depth: Cell::new(0) }.to_string()
+    })
+}
+
+/// Returns a string of each of the two given types, highlighting only the parts of each which
+/// differ from the other. Parts with the same structure in both types, such as the parameters
+/// of two functions taking the same number of arguments, are compared individually so that
+/// a difference deep within a large type is easy to spot. As with `show_types`, type
+/// variables are named consistently across both types.
+pub fn show_type_diff<'b>(typ: &Type, other: &Type, cache: &ModuleCache<'b>) -> (String, String) {
+    let mut typevar_names = HashMap::new();
+    let mut current = 'a';
+    fill_typevar_map(&mut typevar_names, find_all_typevars(typ, false, cache), &mut current);
+    fill_typevar_map(&mut typevar_names, find_all_typevars(other, false, cache), &mut current);
+
+    let printer = TypePrinter::new(GeneralizedType::MonoType(typ.clone()), typevar_names, false, cache);
+    let diff = |a, b| FormatWith(|f: &mut Formatter| printer.fmt_type_diff(a, b, f)).to_string();
+    (diff(typ, other), diff(other, typ))
+}
+
+/// Displays a value by calling the given function with the Formatter
+struct FormatWith<F>(F);
+
+impl<F: Fn(&mut Formatter) -> std::fmt::Result> Display for FormatWith<F> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        (self.0)(f)
+    }
+}
+
 impl<'a, 'b> TypePrinter<'a, 'b> {
     pub fn new(
         typ: GeneralizedType, typevar_names: HashMap<TypeVariableId, String>, debug: bool, cache: &'a ModuleCache<'b>,
     ) -> Self {
-        TypePrinter { typ, typevar_names, debug, cache }
+        TypePrinter { typ, typevar_names, debug, depth: Cell::new(0), cache }
     }
 
     pub fn debug_type(typ: GeneralizedType, cache: &'a ModuleCache<'b>) -> Self {
@@ -153,7 +198,34 @@ impl<'a, 'b> TypePrinter<'a, 'b> {
         }
     }
 
+    /// Format the given type, abbreviating it as `...` if it contains other types and is
+    /// nested more deeply than the maximum set by `error::max_type_depth`. Types are never
+    /// abbreviated when debug printing.
     fn fmt_type(&self, typ: &Type, f: &mut Formatter) -> std::fmt::Result {
+        let is_nested = match typ {
+            Type::Function(_) | Type::Struct(..) => true,
+            Type::TypeApplication(constructor, _) => {
+                !constructor.is_polymorphic_int_type() && !constructor.is_polymorphic_float_type()
+            },
+            _ => false,
+        };
+
+        if !is_nested {
+            return self.fmt_type_contents(typ, f);
+        }
+
+        match error::get_max_type_depth() {
+            Some(max_depth) if !self.debug && self.depth.get() >= max_depth => write!(f, "{}", "...".blue()),
+            _ => {
+                self.depth.set(self.depth.get() + 1);
+                let result = self.fmt_type_contents(typ, f);
+                self.depth.set(self.depth.get() - 1);
+                result
+            },
+        }
+    }
+
+    fn fmt_type_contents(&self, typ: &Type, f: &mut Formatter) -> std::fmt::Result {
         match typ {
             Type::Primitive(primitive) => self.fmt_primitive(primitive, f),
             Type::Function(function) => self.fmt_function(function, f),
@@ -163,6 +235,96 @@ impl<'a, 'b> TypePrinter<'a, 'b> {
             Type::Ref(lifetime) => self.fmt_ref(*lifetime, f),
             Type::Struct(fields, rest) => self.fmt_struct(fields, *rest, f),
             Type::Effects(effects) => self.fmt_effects(effects, f),
+            Type::Error => write!(f, "{}", "error".blue()),
+        }
+    }
+
+    /// Format `typ`, highlighting the parts of it which differ from `other`
+    fn fmt_type_diff(&self, typ: &Type, other: &Type, f: &mut Formatter) -> std::fmt::Result {
+        let typ = self.cache.follow_typebindings_shallow(typ);
+        let other = self.cache.follow_typebindings_shallow(other);
+
+        let text = FormatWith(|f: &mut Formatter| self.fmt_type(typ, f)).to_string();
+        if text == FormatWith(|f: &mut Formatter| self.fmt_type(other, f)).to_string() {
+            return write!(f, "{}", text);
+        }
+
+        match (typ, other) {
+            (Type::Function(function), Type::Function(other))
+                if function.parameters.len() == other.parameters.len()
+                    && function.is_varargs == other.is_varargs
+                    && function.environment.is_unit(self.cache) == other.environment.is_unit(self.cache) =>
+            {
+                self.fmt_function_diff(function, other, f)
+            },
+            (Type::TypeApplication(constructor, args), Type::TypeApplication(other_constructor, other_args))
+                if args.len() == other_args.len()
+                    && constructor.is_pair_type() == other_constructor.is_pair_type()
+                    && !constructor.is_polymorphic_int_type()
+                    && !constructor.is_polymorphic_float_type()
+                    && !other_constructor.is_polymorphic_int_type()
+                    && !other_constructor.is_polymorphic_float_type() =>
+            {
+                write!(f, "{}", "(".blue())?;
+                if constructor.is_pair_type() {
+                    self.fmt_pair_diff(args, other_args, f)?;
+                } else {
+                    self.fmt_type_diff(constructor, other_constructor, f)?;
+                    for (arg, other_arg) in args.iter().zip(other_args) {
+                        write!(f, " ")?;
+                        self.fmt_type_diff(arg, other_arg, f)?;
+                    }
+                }
+                write!(f, "{}", ")".blue())
+            },
+            _ => write!(f, "{}", strip_ansi(&text).red().bold()),
+        }
+    }
+
+    /// Format `function` as in `fmt_function`, highlighting the parts of it which differ from
+    /// `other`. Both must have the same number of parameters and the same kind of arrow.
+    fn fmt_function_diff(&self, function: &FunctionType, other: &FunctionType, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", "(".blue())?;
+        for (i, (param, other_param)) in function.parameters.iter().zip(&other.parameters).enumerate() {
+            self.fmt_type_diff(param, other_param, f)?;
+            write!(f, " ")?;
+
+            if i != function.parameters.len() - 1 {
+                write!(f, "{}", "- ".blue())?;
+            }
+        }
+
+        if function.is_varargs {
+            write!(f, "{}", "... ".blue())?;
+        }
+
+        if function.environment.is_unit(self.cache) {
+            write!(f, "{}", "-> ".blue())?;
+        } else {
+            write!(f, "{}", "=> ".blue())?;
+        }
+
+        self.fmt_type_diff(&function.return_type, &other.return_type, f)?;
+
+        write!(f, "{}", " can ".blue())?;
+        self.fmt_type_diff(&function.effects, &other.effects, f)?;
+
+        write!(f, "{}", ")".blue())
+    }
+
+    /// Format the elements of a pair as in `fmt_pair`, highlighting those which differ from `other`
+    fn fmt_pair_diff(&self, args: &[Type], other: &[Type], f: &mut Formatter) -> std::fmt::Result {
+        self.fmt_type_diff(&args[0], &other[0], f)?;
+
+        write!(f, "{}", ", ".blue())?;
+
+        match (&args[1], &other[1]) {
+            (Type::TypeApplication(constructor, args), Type::TypeApplication(other_constructor, other))
+                if constructor.is_pair_type() && other_constructor.is_pair_type() =>
+            {
+                self.fmt_pair_diff(args, other, f)
+            },
+            (typ, other) => self.fmt_type_diff(typ, other, f),
         }
     }
 
@@ -43,6 +43,64 @@ pub fn dedup<T: Ord + Copy>(vec: Vec<T>) -> Vec<T> {
     }
 }
 
+/// Returns the candidate closest to `name` by edit distance, provided it is close
+/// enough to plausibly be a typo of `name`. Ties are broken alphabetically.
+pub fn closest_match<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = std::cmp::max(1, name.chars().count() / 3);
+
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (levenshtein_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// The number of single-character insertions, deletions, or substitutions
+/// needed to turn string `a` into string `b`
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = Vec::with_capacity(b.len() + 1);
+        row.push(i + 1);
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_row[j] + if a_char == *b_char { 0 } else { 1 };
+            let insertion = row[j] + 1;
+            let deletion = previous_row[j + 1] + 1;
+            row.push(substitution.min(insertion).min(deletion));
+        }
+
+        previous_row = row;
+    }
+
+    previous_row[b.len()]
+}
+
+/// Converts a name such as `fooBar` to snake_case, e.g. `foo_bar`. A run of capital
+/// letters is kept as one word, so `parseHTTPRequest` becomes `parse_http_request`.
+pub fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::with_capacity(name.len() + 4);
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            let previous = chars[i - 1];
+            let next_is_lowercase = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+
+            if previous.is_lowercase() || previous.is_ascii_digit() || (previous.is_uppercase() && next_is_lowercase) {
+                result.push('_');
+            }
+        }
+        result.extend(c.to_lowercase());
+    }
+
+    result
+}
+
 pub fn link(object_filename: &str, binary_filename: &str) {
     // call gcc to compile the bitcode to a binary
     let output = format!("-o{}", binary_filename);
@@ -83,3 +141,60 @@ macro_rules! expect_opt {( $result:expr , $fmt_string:expr $( , $($msg:tt)* )? )
         None => panic!($fmt_string $( , $($msg)* )? ),
     }
 });}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closest_match_finds_the_nearest_candidate() {
+        let candidates = ["Vec", "String", "Strong", "HashMap"];
+        assert_eq!(closest_match("Strin", candidates), Some("String"));
+        assert_eq!(closest_match("HashMpa", candidates), Some("HashMap"));
+
+        // An exact match is the name itself rather than a typo of it
+        assert_eq!(closest_match("Vec", candidates), None);
+    }
+
+    #[test]
+    fn closest_match_rejects_candidates_too_far_away() {
+        assert_eq!(closest_match("Foo", ["Bar", "Baz"]), None);
+        assert_eq!(closest_match("Vector", ["Vec"]), None);
+        assert_eq!(closest_match("x", std::iter::empty()), None);
+    }
+
+    #[test]
+    fn closest_match_breaks_ties_alphabetically() {
+        assert_eq!(closest_match("Strang", ["Strong", "String"]), Some("String"));
+        assert_eq!(closest_match("Strang", ["String", "Strong"]), Some("String"));
+    }
+
+    #[test]
+    fn levenshtein_distance_counts_single_character_edits() {
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+        assert_eq!(levenshtein_distance("", "abc"), 3);
+        assert_eq!(levenshtein_distance("same", "same"), 0);
+        assert_eq!(levenshtein_distance("caf\u{e9}", "cafe"), 1);
+    }
+
+    #[test]
+    fn to_snake_case_splits_words_at_capitals() {
+        assert_eq!(to_snake_case("FooBar"), "foo_bar");
+        assert_eq!(to_snake_case("fooBar"), "foo_bar");
+        assert_eq!(to_snake_case("vec2Len"), "vec2_len");
+    }
+
+    #[test]
+    fn to_snake_case_keeps_acronyms_as_one_word() {
+        assert_eq!(to_snake_case("HTTPServer"), "http_server");
+        assert_eq!(to_snake_case("parseHTTPRequest"), "parse_http_request");
+        assert_eq!(to_snake_case("ID"), "id");
+    }
+
+    #[test]
+    fn to_snake_case_leaves_snake_case_unchanged() {
+        assert_eq!(to_snake_case("foo_bar"), "foo_bar");
+        assert_eq!(to_snake_case("x"), "x");
+        assert_eq!(to_snake_case("_unused"), "_unused");
+    }
+}